@@ -0,0 +1,43 @@
+use crate::Error;
+use parquetry_gen::{Config, ParsedFileSchema};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Regenerate the code for each schema in the directory and print a unified diff for any generated file that differs.
+///
+/// Returns the paths of the generated files that are missing or out of date.
+pub fn check<P: AsRef<Path>, G: AsRef<Path>>(
+    schemas: P,
+    generated: G,
+    suffix: &str,
+    config: Config,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut outdated = vec![];
+
+    for schema in ParsedFileSchema::open_dir(schemas, config, Some(suffix))? {
+        let path = generated.as_ref().join(format!("{}.rs", schema.name));
+        let expected = schema.code()?;
+
+        let found = match std::fs::read_to_string(&path) {
+            Ok(found) => found,
+            Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        if found != expected {
+            let old_header = path.display().to_string();
+            let new_header = format!("{old_header} (regenerated)");
+
+            print!(
+                "{}",
+                similar::TextDiff::from_lines(&found, &expected)
+                    .unified_diff()
+                    .header(&old_header, &new_header)
+            );
+
+            outdated.push(path);
+        }
+    }
+
+    Ok(outdated)
+}
@@ -0,0 +1,319 @@
+use crate::Error;
+use parquet::basic::Type as PhysicalType;
+use parquet::column::reader::ColumnReader;
+use parquet::data_type::{
+    BoolType, ByteArray, ByteArrayType, DoubleType, FixedLenByteArray, FixedLenByteArrayType,
+    FloatType, Int32Type, Int64Type,
+};
+use parquet::file::writer::SerializedColumnWriter;
+use parquet::schema::types::ColumnDescriptor;
+use parquetry_sort::encoding::push_length_prefixed;
+use std::ops::Range;
+
+pub enum Values {
+    Boolean(Vec<bool>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    ByteArray(Vec<ByteArray>),
+    FixedLenByteArray(Vec<FixedLenByteArray>),
+}
+
+/// The buffered values and levels of a leaf column.
+pub struct ColumnData {
+    pub values: Values,
+    pub def_levels: Vec<i16>,
+    pub rep_levels: Vec<i16>,
+}
+
+/// The positions of a single row's levels and values in a column buffer.
+#[derive(Clone, Debug)]
+pub struct RowRange {
+    pub levels: Range<usize>,
+    pub values: Range<usize>,
+}
+
+impl ColumnData {
+    pub const fn new(physical_type: PhysicalType) -> Self {
+        let values = match physical_type {
+            PhysicalType::BOOLEAN => Values::Boolean(vec![]),
+            PhysicalType::INT64 => Values::Int64(vec![]),
+            PhysicalType::FLOAT => Values::Float(vec![]),
+            PhysicalType::DOUBLE => Values::Double(vec![]),
+            PhysicalType::BYTE_ARRAY => Values::ByteArray(vec![]),
+            PhysicalType::FIXED_LEN_BYTE_ARRAY => Values::FixedLenByteArray(vec![]),
+            // The code generator does not support `INT96`, so the schema can't include it.
+            PhysicalType::INT32 | PhysicalType::INT96 => Values::Int32(vec![]),
+        };
+
+        Self {
+            values,
+            def_levels: vec![],
+            rep_levels: vec![],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        match &mut self.values {
+            Values::Boolean(values) => values.clear(),
+            Values::Int32(values) => values.clear(),
+            Values::Int64(values) => values.clear(),
+            Values::Float(values) => values.clear(),
+            Values::Double(values) => values.clear(),
+            Values::ByteArray(values) => values.clear(),
+            Values::FixedLenByteArray(values) => values.clear(),
+        }
+
+        self.def_levels.clear();
+        self.rep_levels.clear();
+    }
+
+    /// Replace the buffer's contents with up to `max_records` rows from the reader, returning the number read.
+    ///
+    /// Levels are always buffered, even if the column's maximum level is zero.
+    pub fn read_records(
+        &mut self,
+        reader: &mut ColumnReader,
+        descriptor: &ColumnDescriptor,
+        max_records: usize,
+    ) -> Result<usize, Error> {
+        self.clear();
+
+        let def_levels = Some(&mut self.def_levels);
+        let rep_levels = Some(&mut self.rep_levels);
+
+        let (records_read, _, levels_read) = match (&mut *reader, &mut self.values) {
+            (ColumnReader::BoolColumnReader(reader), Values::Boolean(values)) => {
+                reader.read_records(max_records, def_levels, rep_levels, values)?
+            }
+            (ColumnReader::Int32ColumnReader(reader), Values::Int32(values)) => {
+                reader.read_records(max_records, def_levels, rep_levels, values)?
+            }
+            (ColumnReader::Int64ColumnReader(reader), Values::Int64(values)) => {
+                reader.read_records(max_records, def_levels, rep_levels, values)?
+            }
+            (ColumnReader::FloatColumnReader(reader), Values::Float(values)) => {
+                reader.read_records(max_records, def_levels, rep_levels, values)?
+            }
+            (ColumnReader::DoubleColumnReader(reader), Values::Double(values)) => {
+                reader.read_records(max_records, def_levels, rep_levels, values)?
+            }
+            (ColumnReader::ByteArrayColumnReader(reader), Values::ByteArray(values)) => {
+                reader.read_records(max_records, def_levels, rep_levels, values)?
+            }
+            (
+                ColumnReader::FixedLenByteArrayColumnReader(reader),
+                Values::FixedLenByteArray(values),
+            ) => reader.read_records(max_records, def_levels, rep_levels, values)?,
+            _ => return Err(Error::UnknownColumn(descriptor.path().string())),
+        };
+
+        if descriptor.max_def_level() == 0 {
+            self.def_levels.resize(levels_read, 0);
+        }
+
+        if descriptor.max_rep_level() == 0 {
+            self.rep_levels.resize(levels_read, 0);
+        }
+
+        Ok(records_read)
+    }
+
+    /// The level and value positions of each buffered row.
+    pub fn rows(&self, descriptor: &ColumnDescriptor) -> Vec<RowRange> {
+        let max_def_level = descriptor.max_def_level();
+        let mut rows: Vec<RowRange> = vec![];
+        let mut value_index = 0;
+
+        for (level_index, (def_level, rep_level)) in
+            self.def_levels.iter().zip(&self.rep_levels).enumerate()
+        {
+            let value_count = usize::from(*def_level == max_def_level);
+
+            match rows.last_mut() {
+                Some(row) if *rep_level > 0 => {
+                    row.levels.end += 1;
+                    row.values.end += value_count;
+                }
+                _ => rows.push(RowRange {
+                    levels: level_index..level_index + 1,
+                    values: value_index..value_index + value_count,
+                }),
+            }
+
+            value_index += value_count;
+        }
+
+        rows
+    }
+
+    /// Append the levels and values of a row to an encoded row.
+    ///
+    /// The encoding is a level count followed by the definition and repetition levels and then the values, with
+    /// byte arrays length-prefixed.
+    pub fn encode_row(&self, row: &RowRange, bytes: &mut Vec<u8>) {
+        bytes.extend(
+            u32::try_from(row.levels.len())
+                .unwrap_or(u32::MAX)
+                .to_be_bytes(),
+        );
+
+        for index in row.levels.clone() {
+            bytes.extend(self.def_levels[index].to_be_bytes());
+            bytes.extend(self.rep_levels[index].to_be_bytes());
+        }
+
+        let values = row.values.clone();
+
+        match &self.values {
+            Values::Boolean(values_buffer) => {
+                bytes.extend(values_buffer[values].iter().map(|value| u8::from(*value)));
+            }
+            Values::Int32(values_buffer) => {
+                for value in &values_buffer[values] {
+                    bytes.extend(value.to_be_bytes());
+                }
+            }
+            Values::Int64(values_buffer) => {
+                for value in &values_buffer[values] {
+                    bytes.extend(value.to_be_bytes());
+                }
+            }
+            Values::Float(values_buffer) => {
+                for value in &values_buffer[values] {
+                    bytes.extend(value.to_be_bytes());
+                }
+            }
+            Values::Double(values_buffer) => {
+                for value in &values_buffer[values] {
+                    bytes.extend(value.to_be_bytes());
+                }
+            }
+            Values::ByteArray(values_buffer) => {
+                for value in &values_buffer[values] {
+                    push_length_prefixed(bytes, value.data());
+                }
+            }
+            Values::FixedLenByteArray(values_buffer) => {
+                for value in &values_buffer[values] {
+                    push_length_prefixed(bytes, value.data());
+                }
+            }
+        }
+    }
+
+    /// Decode the levels and values of a row (see `encode_row`), returning the remaining bytes.
+    pub fn decode_row<'a>(
+        &mut self,
+        descriptor: &ColumnDescriptor,
+        bytes: &'a [u8],
+    ) -> Result<&'a [u8], Error> {
+        let mut decoder = Decoder { bytes };
+        let level_count = decoder.take_array::<4>().map(u32::from_be_bytes)?;
+        let mut value_count = 0;
+
+        for _ in 0..level_count {
+            let def_level = decoder.take_array::<2>().map(i16::from_be_bytes)?;
+            let rep_level = decoder.take_array::<2>().map(i16::from_be_bytes)?;
+
+            self.def_levels.push(def_level);
+            self.rep_levels.push(rep_level);
+
+            if def_level == descriptor.max_def_level() {
+                value_count += 1;
+            }
+        }
+
+        for _ in 0..value_count {
+            match &mut self.values {
+                Values::Boolean(values) => values.push(decoder.take_array::<1>()?[0] != 0),
+                Values::Int32(values) => {
+                    values.push(decoder.take_array().map(i32::from_be_bytes)?);
+                }
+                Values::Int64(values) => {
+                    values.push(decoder.take_array().map(i64::from_be_bytes)?);
+                }
+                Values::Float(values) => {
+                    values.push(decoder.take_array().map(f32::from_be_bytes)?);
+                }
+                Values::Double(values) => {
+                    values.push(decoder.take_array().map(f64::from_be_bytes)?);
+                }
+                Values::ByteArray(values) => {
+                    values.push(decoder.take_length_prefixed()?.to_vec().into());
+                }
+                Values::FixedLenByteArray(values) => {
+                    values.push(ByteArray::from(decoder.take_length_prefixed()?.to_vec()).into());
+                }
+            }
+        }
+
+        Ok(decoder.bytes)
+    }
+
+    /// Write the buffered values and levels to a column chunk.
+    pub fn write(
+        &self,
+        column_writer: &mut SerializedColumnWriter<'_>,
+        descriptor: &ColumnDescriptor,
+    ) -> Result<(), Error> {
+        let def_levels = (descriptor.max_def_level() > 0).then_some(self.def_levels.as_slice());
+        let rep_levels = (descriptor.max_rep_level() > 0).then_some(self.rep_levels.as_slice());
+
+        match &self.values {
+            Values::Boolean(values) => column_writer
+                .typed::<BoolType>()
+                .write_batch(values, def_levels, rep_levels),
+            Values::Int32(values) => column_writer
+                .typed::<Int32Type>()
+                .write_batch(values, def_levels, rep_levels),
+            Values::Int64(values) => column_writer
+                .typed::<Int64Type>()
+                .write_batch(values, def_levels, rep_levels),
+            Values::Float(values) => column_writer
+                .typed::<FloatType>()
+                .write_batch(values, def_levels, rep_levels),
+            Values::Double(values) => column_writer
+                .typed::<DoubleType>()
+                .write_batch(values, def_levels, rep_levels),
+            Values::ByteArray(values) => column_writer
+                .typed::<ByteArrayType>()
+                .write_batch(values, def_levels, rep_levels),
+            Values::FixedLenByteArray(values) => column_writer
+                .typed::<FixedLenByteArrayType>()
+                .write_batch(values, def_levels, rep_levels),
+        }?;
+
+        Ok(())
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    const fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.bytes.len() {
+            Err(Error::InvalidEncodedRow)
+        } else {
+            let (value, rest) = self.bytes.split_at(len);
+            self.bytes = rest;
+
+            Ok(value)
+        }
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        self.take(N)?
+            .try_into()
+            .map_err(|_| Error::InvalidEncodedRow)
+    }
+
+    fn take_length_prefixed(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.take_array::<4>().map(u32::from_be_bytes)?;
+
+        self.take(usize::try_from(len).map_err(|_| Error::InvalidEncodedRow)?)
+    }
+}
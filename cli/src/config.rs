@@ -0,0 +1,46 @@
+use cli_helpers::prelude::*;
+use parquetry_gen::Config;
+
+/// Code generation options (see `parquetry_gen::Config`).
+// Each flag corresponds to one independent command-line switch (and one `Config` field).
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Parser)]
+pub struct ConfigOpts {
+    /// Base derives for generated types (comma-separated)
+    #[clap(long, value_delimiter = ',')]
+    derives: Option<Vec<String>>,
+    /// Generate first, last, min, max, and length sort columns for lists of primitive values
+    #[clap(long)]
+    derived_sort_columns: bool,
+    /// Do not format generated code
+    #[clap(long)]
+    no_format: bool,
+    /// Do not derive serde instances
+    #[clap(long)]
+    no_serde: bool,
+    /// Do not generate tests
+    #[clap(long)]
+    no_tests: bool,
+    /// Generate borrowed views
+    #[clap(long)]
+    views: bool,
+}
+
+impl ConfigOpts {
+    pub fn config(&self) -> Config {
+        let base_derives = self
+            .derives
+            .clone()
+            .unwrap_or_else(|| Config::default().base_derives);
+
+        // No defaults are filled in here, so that new fields must be added as options.
+        Config {
+            base_derives,
+            derived_sort_columns: self.derived_sort_columns,
+            format: !self.no_format,
+            serde_support: !self.no_serde,
+            tests: !self.no_tests,
+            views: self.views,
+        }
+    }
+}
@@ -0,0 +1,650 @@
+use crate::{
+    Error,
+    column::{ColumnData, Values},
+};
+use chrono::{DateTime, NaiveDate};
+use parquet::basic::Compression;
+use parquet::data_type::ByteArray;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquetry_gen::schema::{GenColumn, GenField, GenType};
+use parquetry_gen::{Config, ParsedFileSchema};
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum InputFormat {
+    /// One JSON object per line
+    Jsonl,
+    /// Comma-separated values with a header row, where nested fields have dot-separated names and lists are JSON
+    Csv,
+}
+
+impl InputFormat {
+    /// Guess the format from the file extension (JSON Lines is the default).
+    fn from_path(path: &Path) -> Self {
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+        {
+            Self::Csv
+        } else {
+            Self::Jsonl
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ConvertOptions {
+    pub format: Option<InputFormat>,
+    /// The maximum number of rows in each row group
+    pub row_group_size: usize,
+    pub compression: Compression,
+}
+
+/// Convert JSON Lines or CSV input into a Parquet file with the given schema, returning the number of rows written.
+pub fn convert<S: AsRef<Path>, I: AsRef<Path>, O: AsRef<Path>>(
+    schema: S,
+    input: I,
+    output: O,
+    options: &ConvertOptions,
+) -> Result<usize, Error> {
+    let source = std::fs::read_to_string(schema.as_ref())?;
+    let schema = ParsedFileSchema::open(schema, Config::default())?;
+
+    let properties = WriterProperties::builder()
+        .set_compression(options.compression)
+        .set_key_value_metadata(Some(vec![KeyValue::new(
+            parquetry::metadata::HASH_KEY.to_string(),
+            parquetry::metadata::source_hash(&source),
+        )]))
+        .build();
+
+    let mut writer = SerializedFileWriter::new(
+        File::create(output)?,
+        schema.descriptor.root_schema_ptr(),
+        Arc::new(properties),
+    )?;
+
+    let input = input.as_ref();
+    let rows = match options
+        .format
+        .unwrap_or_else(|| InputFormat::from_path(input))
+    {
+        InputFormat::Jsonl => read_jsonl(input)?,
+        InputFormat::Csv => read_csv(input)?,
+    };
+
+    let mut shredder = Shredder::new(schema.schema.gen_columns());
+
+    for row in rows {
+        shredder.add_row(&schema.schema.gen_fields, &row?)?;
+
+        if shredder.row_group_row_count() >= options.row_group_size {
+            shredder.write_row_group(&mut writer)?;
+        }
+    }
+
+    if shredder.row_group_row_count() > 0 {
+        shredder.write_row_group(&mut writer)?;
+    }
+
+    writer.close()?;
+
+    Ok(shredder.row_count())
+}
+
+type Rows = Box<dyn Iterator<Item = Result<Value, Error>>>;
+
+fn read_jsonl(input: &Path) -> Result<Rows, Error> {
+    let reader = BufReader::new(File::open(input)?);
+
+    Ok(Box::new(reader.lines().filter_map(|line| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(serde_json::from_str(&line).map_err(Error::from)),
+        Err(error) => Some(Err(error.into())),
+    })))
+}
+
+fn read_csv(input: &Path) -> Result<Rows, Error> {
+    let mut reader = csv::Reader::from_path(input)?;
+    let headers = reader.headers()?.clone();
+
+    Ok(Box::new(reader.into_records().map(move |record| {
+        let record = record?;
+        let mut object = Map::new();
+
+        for (header, cell) in headers.iter().zip(record.iter()) {
+            // Empty cells are treated as nulls.
+            if !cell.is_empty() {
+                let path = header.split('.').collect::<Vec<_>>();
+                insert_path(&mut object, &path, Value::String(cell.to_string()));
+            }
+        }
+
+        Ok(Value::Object(object))
+    })))
+}
+
+fn insert_path(object: &mut Map<String, Value>, path: &[&str], value: Value) {
+    match path {
+        [] => {}
+        [name] => {
+            object.insert((*name).to_string(), value);
+        }
+        [name, rest @ ..] => {
+            if let Value::Object(child) = object
+                .entry((*name).to_string())
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                insert_path(child, rest, value);
+            }
+        }
+    }
+}
+
+/// Interprets the generated schema at runtime to compute the values and levels for each column.
+///
+/// Rows are represented as JSON objects (as in `convert` input), or as encoded rows (see `ColumnData::encode_row`).
+pub struct Shredder {
+    gen_columns: Vec<GenColumn>,
+    columns: Vec<ColumnData>,
+    row_index: usize,
+    row_group_row_count: usize,
+}
+
+impl Shredder {
+    pub fn new(gen_columns: Vec<GenColumn>) -> Self {
+        let columns = gen_columns
+            .iter()
+            .map(|gen_column| ColumnData::new(gen_column.descriptor.physical_type()))
+            .collect();
+
+        Self {
+            gen_columns,
+            columns,
+            row_index: 0,
+            row_group_row_count: 0,
+        }
+    }
+
+    /// The total number of rows added.
+    pub const fn row_count(&self) -> usize {
+        self.row_index
+    }
+
+    /// The number of rows added since the last row group was written.
+    pub const fn row_group_row_count(&self) -> usize {
+        self.row_group_row_count
+    }
+
+    pub fn add_row(&mut self, gen_fields: &[GenField], row: &Value) -> Result<(), Error> {
+        let Value::Object(row) = row else {
+            return Err(self.invalid(&[], "expected object"));
+        };
+
+        for gen_field in gen_fields {
+            self.add_optional(
+                &gen_field.gen_type,
+                gen_field.optional,
+                row.get(&gen_field.name),
+                0,
+                0,
+                &[gen_field.name.as_str()],
+            )?;
+        }
+
+        self.row_index += 1;
+        self.row_group_row_count += 1;
+
+        Ok(())
+    }
+
+    /// Add a row encoded as the levels and values of each column in order.
+    pub fn add_encoded_row(&mut self, mut bytes: &[u8]) -> Result<(), Error> {
+        for (gen_column, column) in self.gen_columns.iter().zip(&mut self.columns) {
+            bytes = column.decode_row(&gen_column.descriptor, bytes)?;
+        }
+
+        self.row_index += 1;
+        self.row_group_row_count += 1;
+
+        Ok(())
+    }
+
+    fn add_optional(
+        &mut self,
+        gen_type: &GenType,
+        optional: bool,
+        value: Option<&Value>,
+        def_level: i16,
+        rep_level: i16,
+        path: &[&str],
+    ) -> Result<(), Error> {
+        match value {
+            None | Some(Value::Null) => {
+                if optional {
+                    self.add_nulls(gen_type, def_level, rep_level);
+                    Ok(())
+                } else {
+                    Err(self.invalid(path, "missing required value"))
+                }
+            }
+            Some(value) => self.add_value(
+                gen_type,
+                value,
+                def_level + i16::from(optional),
+                rep_level,
+                path,
+            ),
+        }
+    }
+
+    fn add_value(
+        &mut self,
+        gen_type: &GenType,
+        value: &Value,
+        def_level: i16,
+        rep_level: i16,
+        path: &[&str],
+    ) -> Result<(), Error> {
+        match gen_type {
+            GenType::Column(gen_column) => {
+                let index = gen_column.index;
+
+                self.push_value(index, value)
+                    .map_err(|message| self.invalid(path, message))?;
+                self.columns[index].def_levels.push(def_level);
+                self.columns[index].rep_levels.push(rep_level);
+
+                Ok(())
+            }
+            GenType::Struct { gen_fields, .. } => {
+                let Value::Object(object) = parse_nested(value) else {
+                    return Err(self.invalid(path, "expected object"));
+                };
+
+                for gen_field in gen_fields {
+                    let mut field_path = path.to_vec();
+                    field_path.push(&gen_field.name);
+
+                    self.add_optional(
+                        &gen_field.gen_type,
+                        gen_field.optional,
+                        object.get(&gen_field.name),
+                        def_level,
+                        rep_level,
+                        &field_path,
+                    )?;
+                }
+
+                Ok(())
+            }
+            GenType::List {
+                element_optional,
+                element_gen_type,
+                def_depth,
+                rep_depth,
+                ..
+            } => {
+                let Value::Array(elements) = parse_nested(value) else {
+                    return Err(self.invalid(path, "expected array"));
+                };
+
+                if elements.is_empty() {
+                    self.add_nulls(gen_type, def_level, rep_level);
+                }
+
+                let element_def_level = level(*def_depth);
+                let element_rep_level = level(*rep_depth);
+
+                for (index, element) in elements.iter().enumerate() {
+                    let index_name = index.to_string();
+                    let mut element_path = path.to_vec();
+                    element_path.push(&index_name);
+
+                    self.add_optional(
+                        element_gen_type,
+                        *element_optional,
+                        Some(element),
+                        element_def_level,
+                        if index == 0 {
+                            rep_level
+                        } else {
+                            element_rep_level
+                        },
+                        &element_path,
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Add a level without a value for every column in the type.
+    fn add_nulls(&mut self, gen_type: &GenType, def_level: i16, rep_level: i16) {
+        for column in &mut self.columns[gen_type.column_indices()] {
+            column.def_levels.push(def_level);
+            column.rep_levels.push(rep_level);
+        }
+    }
+
+    /// Convert a value in the same way as a generated type's field would be converted for writing.
+    fn push_value(&mut self, index: usize, value: &Value) -> Result<(), String> {
+        let gen_column = &self.gen_columns[index];
+        let variant = gen_column.mapping.row_field_variant();
+
+        match (&mut self.columns[index].values, variant) {
+            (Values::Boolean(values), "Bool") => values.push(match value {
+                Value::Bool(value) => *value,
+                other => parse_number(other).ok_or("expected boolean")?,
+            }),
+            (Values::Int32(values), "Int") => {
+                values.push(parse_number(value).ok_or("expected 32-bit integer")?);
+            }
+            (Values::Int32(values), "UInt") => values.push(i32::from_ne_bytes(
+                parse_number::<u32>(value)
+                    .ok_or("expected unsigned 32-bit integer")?
+                    .to_ne_bytes(),
+            )),
+            (Values::Int32(values), "Date") => values.push(parse_date(value)?),
+            (Values::Int64(values), "Long") => {
+                values.push(parse_number(value).ok_or("expected 64-bit integer")?);
+            }
+            (Values::Int64(values), "ULong") => values.push(i64::from_ne_bytes(
+                parse_number::<u64>(value)
+                    .ok_or("expected unsigned 64-bit integer")?
+                    .to_ne_bytes(),
+            )),
+            (Values::Int64(values), "TimestampMillis") => {
+                values.push(parse_timestamp(value, DateTime::timestamp_millis)?);
+            }
+            (Values::Int64(values), "TimestampMicros") => {
+                values.push(parse_timestamp(value, DateTime::timestamp_micros)?);
+            }
+            (Values::Float(values), "Float") => {
+                values.push(parse_number(value).ok_or("expected number")?);
+            }
+            (Values::Double(values), "Double") => {
+                values.push(parse_number(value).ok_or("expected number")?);
+            }
+            (Values::ByteArray(values), "Str") => match value {
+                Value::String(value) => values.push(value.as_str().into()),
+                _ => return Err("expected string".to_string()),
+            },
+            (Values::ByteArray(values), "Bytes") => values.push(parse_bytes(value)?.into()),
+            (Values::FixedLenByteArray(values), "Bytes") => {
+                let bytes = parse_bytes(value)?;
+                let expected_len = gen_column.descriptor.type_length();
+
+                if usize::try_from(expected_len).ok() != Some(bytes.len()) {
+                    return Err(format!(
+                        "expected {expected_len} bytes, found {}",
+                        bytes.len()
+                    ));
+                }
+
+                values.push(ByteArray::from(bytes).into());
+            }
+            (_, variant) => return Err(format!("unsupported column type {variant}")),
+        }
+
+        Ok(())
+    }
+
+    pub fn write_row_group<W: std::io::Write + Send>(
+        &mut self,
+        writer: &mut SerializedFileWriter<W>,
+    ) -> Result<(), Error> {
+        let mut row_group_writer = writer.next_row_group()?;
+
+        for (gen_column, column) in self.gen_columns.iter().zip(&mut self.columns) {
+            let mut column_writer = row_group_writer
+                .next_column()?
+                .ok_or_else(|| Error::UnknownColumn(gen_column.descriptor.path().string()))?;
+
+            column.write(&mut column_writer, &gen_column.descriptor)?;
+            column_writer.close()?;
+            column.clear();
+        }
+
+        row_group_writer.close()?;
+        self.row_group_row_count = 0;
+
+        Ok(())
+    }
+
+    fn invalid<M: Into<String>>(&self, path: &[&str], message: M) -> Error {
+        Error::InvalidInput {
+            row_index: self.row_index,
+            path: path.join("."),
+            message: message.into(),
+        }
+    }
+}
+
+/// CSV cells for groups and lists are JSON strings.
+fn parse_nested(value: &Value) -> Value {
+    match value {
+        Value::String(value) => serde_json::from_str(value).unwrap_or(Value::Null),
+        other => other.clone(),
+    }
+}
+
+fn parse_number<T: FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::Number(value) => value.to_string().parse().ok(),
+        Value::String(value) => value.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Dates can be given as `YYYY-MM-DD` strings or as days since the Unix epoch.
+fn parse_date(value: &Value) -> Result<i32, String> {
+    parse_number(value)
+        .or_else(|| match value {
+            Value::String(value) => NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                .ok()
+                .zip(NaiveDate::from_ymd_opt(1970, 1, 1))
+                .and_then(|(date, epoch)| {
+                    i32::try_from(date.signed_duration_since(epoch).num_days()).ok()
+                }),
+            _ => None,
+        })
+        .ok_or_else(|| "expected date".to_string())
+}
+
+/// Timestamps can be given as RFC 3339 strings or as numbers in the column's unit since the Unix epoch.
+fn parse_timestamp<F: Fn(&DateTime<chrono::FixedOffset>) -> i64>(
+    value: &Value,
+    from_date_time: F,
+) -> Result<i64, String> {
+    parse_number(value)
+        .or_else(|| match value {
+            Value::String(value) => DateTime::parse_from_rfc3339(value.trim())
+                .ok()
+                .map(|timestamp| from_date_time(&timestamp)),
+            _ => None,
+        })
+        .ok_or_else(|| "expected RFC 3339 or epoch timestamp".to_string())
+}
+
+/// Byte arrays can be given as arrays of numbers or as strings.
+fn parse_bytes(value: &Value) -> Result<Vec<u8>, String> {
+    match value {
+        Value::String(value) => Ok(value.as_bytes().to_vec()),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| parse_number(value).ok_or_else(|| "expected byte".to_string()))
+            .collect(),
+        _ => Err("expected byte array".to_string()),
+    }
+}
+
+fn level(depth: usize) -> i16 {
+    i16::try_from(depth).unwrap_or(i16::MAX)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ConvertOptions, InputFormat};
+    use crate::{Error, column::ColumnData, value};
+    use parquet::basic::Compression;
+    use parquet::file::reader::FileReader;
+    use parquet::file::serialized_reader::SerializedFileReader;
+    use serde_json::{Value, json};
+    use std::fs::File;
+
+    const NESTED_SCHEMA: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../test/src/schemas/nested.parquet.txt"
+    );
+    const TWO_LIST_LEVELS_SCHEMA: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../test/src/schemas/two_list_levels.parquet.txt"
+    );
+
+    struct Converted {
+        /// The definition and repetition levels of each column, across all row groups
+        levels: Vec<(Vec<i16>, Vec<i16>)>,
+        rows: Vec<Value>,
+    }
+
+    fn convert(schema: &str, rows: &[Value]) -> Result<Converted, Error> {
+        let temp_dir = tempfile::tempdir()?;
+        let input = temp_dir.path().join("input.jsonl");
+        let output = temp_dir.path().join("output.parquet");
+
+        std::fs::write(
+            &input,
+            rows.iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )?;
+
+        let row_count = super::convert(
+            schema,
+            &input,
+            &output,
+            &ConvertOptions {
+                format: Some(InputFormat::Jsonl),
+                row_group_size: 2,
+                compression: Compression::SNAPPY,
+            },
+        )?;
+
+        assert_eq!(row_count, rows.len());
+
+        let reader = SerializedFileReader::new(File::open(&output)?)?;
+        let descriptor = reader.metadata().file_metadata().schema_descr_ptr();
+        let mut levels = vec![(vec![], vec![]); descriptor.num_columns()];
+
+        for row_group_index in 0..reader.num_row_groups() {
+            let row_group = reader.get_row_group(row_group_index)?;
+
+            for (column_index, column) in descriptor.columns().iter().enumerate() {
+                let mut reader = row_group.get_column_reader(column_index)?;
+                let mut buffer = ColumnData::new(column.physical_type());
+
+                buffer.read_records(&mut reader, column, usize::MAX)?;
+                levels[column_index].0.extend(buffer.def_levels);
+                levels[column_index].1.extend(buffer.rep_levels);
+            }
+        }
+
+        let rows = reader
+            .get_row_iter(None)?
+            .map(|row| Ok(value::row_to_json(&row?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Converted { levels, rows })
+    }
+
+    #[test]
+    fn convert_nested() {
+        let rows = vec![
+            json!({
+                "a": 1,
+                "bc": "2024-01-02T03:04:05.678Z",
+                "cde": null,
+                "foox": null,
+            }),
+            json!({
+                "a": 2,
+                "bc": "1969-12-31T23:59:59.999Z",
+                "cde": -3,
+                "foox": { "fghi": "x", "bar": { "jklmnop": "y", "quxes": [] } },
+            }),
+            json!({
+                "a": u64::MAX,
+                "bc": "1970-01-01T00:00:00Z",
+                "cde": i32::MAX,
+                "foox": {
+                    "fghi": "",
+                    "bar": {
+                        "jklmnop": "z",
+                        "quxes": [
+                            null,
+                            { "xuqes": null, "oof": "a" },
+                            { "xuqes": [], "oof": "b" },
+                            { "xuqes": [true, false], "oof": "c" },
+                        ],
+                    },
+                },
+            }),
+        ];
+
+        let converted = convert(NESTED_SCHEMA, &rows).unwrap();
+
+        assert_eq!(converted.rows, rows);
+        assert_eq!(converted.levels[2], (vec![0, 1, 1], vec![0, 0, 0]));
+        assert_eq!(converted.levels[3], (vec![0, 1, 1], vec![0, 0, 0]));
+        assert_eq!(
+            converted.levels[5],
+            (vec![0, 1, 2, 3, 4, 5, 5], vec![0, 0, 0, 1, 1, 1, 2])
+        );
+        assert_eq!(
+            converted.levels[6],
+            (vec![0, 1, 2, 3, 3, 3], vec![0, 0, 0, 1, 1, 1])
+        );
+    }
+
+    #[test]
+    fn convert_two_list_levels() {
+        let rows = vec![
+            json!({ "values": [] }),
+            json!({ "values": [[], [true], [false, true]] }),
+            json!({ "values": [[false]] }),
+        ];
+
+        let converted = convert(TWO_LIST_LEVELS_SCHEMA, &rows).unwrap();
+
+        assert_eq!(converted.rows, rows);
+        assert_eq!(
+            converted.levels[0],
+            (vec![0, 1, 2, 2, 2, 2], vec![0, 0, 1, 1, 2, 0])
+        );
+    }
+
+    #[test]
+    fn convert_missing_required_value() {
+        let rows = vec![
+            json!({ "a": 1, "bc": 0 }),
+            json!({ "a": 2, "bc": 0, "foox": { "fghi": "x", "bar": { "quxes": [] } } }),
+        ];
+
+        let result = convert(NESTED_SCHEMA, &rows);
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidInput { row_index: 1, path, .. }) if path == "foox.bar.jklmnop"
+        ));
+    }
+}
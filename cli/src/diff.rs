@@ -0,0 +1,415 @@
+use crate::{
+    Error,
+    validate::{self, Violation},
+    value,
+};
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::record::Row;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Clone, Debug)]
+pub struct DiffOptions {
+    /// Dot-separated path of a column to match rows by (rows are matched by position if this is not provided)
+    pub key: Option<String>,
+    /// The maximum absolute difference between floating point values that are considered equal
+    pub tolerance: f64,
+    /// The maximum number of differing rows to include in the summary
+    pub max_rows: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Summary {
+    pub schema_differences: Vec<Violation>,
+    pub left_row_count: usize,
+    pub right_row_count: usize,
+    pub changed_row_count: usize,
+    pub left_only_row_count: usize,
+    pub right_only_row_count: usize,
+    /// The first differing rows
+    pub rows: Vec<RowDiff>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RowDiff {
+    /// The row index, or the key value if rows are matched by key
+    pub label: String,
+    pub kind: RowDiffKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum RowDiffKind {
+    Changed(Vec<FieldDiff>),
+    LeftOnly,
+    RightOnly,
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldDiff {
+    pub path: String,
+    pub left: Value,
+    pub right: Value,
+}
+
+pub fn diff<L: AsRef<Path>, R: AsRef<Path>>(
+    left: L,
+    right: R,
+    options: &DiffOptions,
+) -> Result<Summary, Error> {
+    let left_reader = SerializedFileReader::new(File::open(left)?)?;
+    let right_reader = SerializedFileReader::new(File::open(right)?)?;
+
+    let mut summary = Summary {
+        schema_differences: validate::compare_schemas(
+            left_reader.metadata().file_metadata().schema(),
+            right_reader.metadata().file_metadata().schema(),
+        ),
+        left_row_count: 0,
+        right_row_count: 0,
+        changed_row_count: 0,
+        left_only_row_count: 0,
+        right_only_row_count: 0,
+        rows: vec![],
+    };
+
+    let left_rows = left_reader.get_row_iter(None)?;
+    let mut right_rows = right_reader.get_row_iter(None)?;
+
+    match &options.key {
+        Some(key) => {
+            let key_path = key.split('.').map(str::to_string).collect::<Vec<_>>();
+
+            // The right file's rows are held in memory, indexed by key.
+            let mut right_by_key = HashMap::<String, VecDeque<Value>>::new();
+            let mut right_keys = vec![];
+
+            for row in right_rows {
+                let row = row?;
+                let key = key_value(&row, &key_path);
+
+                let rows = right_by_key.entry(key.clone()).or_default();
+
+                if rows.is_empty() {
+                    right_keys.push(key);
+                }
+
+                rows.push_back(value::row_to_json(&row));
+                summary.right_row_count += 1;
+            }
+
+            for row in left_rows {
+                let row = row?;
+                let key = key_value(&row, &key_path);
+                summary.left_row_count += 1;
+
+                match right_by_key.get_mut(&key).and_then(VecDeque::pop_front) {
+                    Some(right) => {
+                        summary.compare(key, &value::row_to_json(&row), &right, options);
+                    }
+                    None => summary.add(key, RowDiffKind::LeftOnly, options),
+                }
+            }
+
+            for key in right_keys {
+                let remaining = right_by_key.get(&key).map_or(0, VecDeque::len);
+
+                for _ in 0..remaining {
+                    summary.add(key.clone(), RowDiffKind::RightOnly, options);
+                }
+            }
+        }
+        None => {
+            for (index, left) in left_rows.enumerate() {
+                let left = left?;
+                summary.left_row_count += 1;
+
+                match right_rows.next().transpose()? {
+                    Some(right) => {
+                        summary.right_row_count += 1;
+                        summary.compare(
+                            index.to_string(),
+                            &value::row_to_json(&left),
+                            &value::row_to_json(&right),
+                            options,
+                        );
+                    }
+                    None => summary.add(index.to_string(), RowDiffKind::LeftOnly, options),
+                }
+            }
+
+            for right in right_rows {
+                right?;
+                summary.add(
+                    summary.right_row_count.to_string(),
+                    RowDiffKind::RightOnly,
+                    options,
+                );
+                summary.right_row_count += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+impl Summary {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.schema_differences.is_empty()
+            && self.changed_row_count == 0
+            && self.left_only_row_count == 0
+            && self.right_only_row_count == 0
+    }
+
+    pub fn write<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        if !self.schema_differences.is_empty() {
+            writeln!(output, "schemas differ:")?;
+
+            for difference in &self.schema_differences {
+                writeln!(output, "  {difference}")?;
+            }
+        }
+
+        writeln!(
+            output,
+            "rows: {} (left), {} (right)",
+            self.left_row_count, self.right_row_count
+        )?;
+        writeln!(output, "changed rows: {}", self.changed_row_count)?;
+        writeln!(output, "rows only in left: {}", self.left_only_row_count)?;
+        writeln!(output, "rows only in right: {}", self.right_only_row_count)?;
+
+        for row in &self.rows {
+            match &row.kind {
+                RowDiffKind::Changed(fields) => {
+                    writeln!(output, "row {}:", row.label)?;
+
+                    for field in fields {
+                        writeln!(
+                            output,
+                            "  {}: {} != {}",
+                            field.path, field.left, field.right
+                        )?;
+                    }
+                }
+                RowDiffKind::LeftOnly => writeln!(output, "row {}: only in left", row.label)?,
+                RowDiffKind::RightOnly => writeln!(output, "row {}: only in right", row.label)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compare(&mut self, label: String, left: &Value, right: &Value, options: &DiffOptions) {
+        let mut fields = vec![];
+        compare_values(left, right, &mut vec![], options.tolerance, &mut fields);
+
+        if !fields.is_empty() {
+            self.add(label, RowDiffKind::Changed(fields), options);
+        }
+    }
+
+    fn add(&mut self, label: String, kind: RowDiffKind, options: &DiffOptions) {
+        match kind {
+            RowDiffKind::Changed(_) => self.changed_row_count += 1,
+            RowDiffKind::LeftOnly => self.left_only_row_count += 1,
+            RowDiffKind::RightOnly => self.right_only_row_count += 1,
+        }
+
+        if self.rows.len() < options.max_rows {
+            self.rows.push(RowDiff { label, kind });
+        }
+    }
+}
+
+fn key_value(row: &Row, path: &[String]) -> String {
+    value::lookup(row, path)
+        .map_or(Value::Null, value::field_to_json)
+        .to_string()
+}
+
+fn compare_values(
+    left: &Value,
+    right: &Value,
+    path: &mut Vec<String>,
+    tolerance: f64,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    match (left, right) {
+        (Value::Object(left_fields), Value::Object(right_fields)) => {
+            for (name, left_value) in left_fields {
+                path.push(name.clone());
+                compare_values(
+                    left_value,
+                    right_fields.get(name).unwrap_or(&Value::Null),
+                    path,
+                    tolerance,
+                    diffs,
+                );
+                path.pop();
+            }
+
+            for (name, right_value) in right_fields {
+                if !left_fields.contains_key(name) {
+                    path.push(name.clone());
+                    compare_values(&Value::Null, right_value, path, tolerance, diffs);
+                    path.pop();
+                }
+            }
+        }
+        (Value::Array(left_elements), Value::Array(right_elements))
+            if left_elements.len() == right_elements.len() =>
+        {
+            for (index, (left_element, right_element)) in
+                left_elements.iter().zip(right_elements).enumerate()
+            {
+                path.push(index.to_string());
+                compare_values(left_element, right_element, path, tolerance, diffs);
+                path.pop();
+            }
+        }
+        (Value::Number(left_number), Value::Number(right_number))
+            if left_number.is_f64() || right_number.is_f64() =>
+        {
+            let within_tolerance = left_number.as_f64().zip(right_number.as_f64()).is_some_and(
+                |(left_value, right_value)| (left_value - right_value).abs() <= tolerance,
+            );
+
+            if !within_tolerance {
+                diffs.push(field_diff(path, left, right));
+            }
+        }
+        _ => {
+            if left != right {
+                diffs.push(field_diff(path, left, right));
+            }
+        }
+    }
+}
+
+fn field_diff(path: &[String], left: &Value, right: &Value) -> FieldDiff {
+    FieldDiff {
+        path: path.join("."),
+        left: left.clone(),
+        right: right.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DiffOptions, RowDiffKind, Summary};
+    use crate::test_data;
+    use parquet::basic::Compression;
+    use parquetry_test::simple::Simple;
+
+    fn values() -> Vec<Simple> {
+        (0..6)
+            .map(|abc| test_data::simple(abc, Some("a"), Some(f64::from(abc) / 3.0)))
+            .collect()
+    }
+
+    fn diff(left: Vec<Simple>, right: Vec<Simple>, key: Option<&str>, tolerance: f64) -> Summary {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let left_path = temp_dir.path().join("left.parquet");
+        let right_path = temp_dir.path().join("right.parquet");
+
+        test_data::write_simple(&left_path, vec![left], Compression::UNCOMPRESSED);
+        test_data::write_simple(&right_path, vec![right], Compression::SNAPPY);
+
+        super::diff(
+            &left_path,
+            &right_path,
+            &DiffOptions {
+                key: key.map(str::to_string),
+                tolerance,
+                max_rows: 10,
+            },
+        )
+        .unwrap()
+    }
+
+    fn labels(summary: &Summary) -> Vec<(&str, Vec<&str>)> {
+        summary
+            .rows
+            .iter()
+            .map(|row| {
+                let paths = match &row.kind {
+                    RowDiffKind::Changed(fields) => {
+                        fields.iter().map(|field| field.path.as_str()).collect()
+                    }
+                    RowDiffKind::LeftOnly => vec!["left only"],
+                    RowDiffKind::RightOnly => vec!["right only"],
+                };
+
+                (row.label.as_str(), paths)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diff_by_key() {
+        let left = values();
+        let mut right = left.clone();
+        right.reverse();
+
+        let summary = diff(left.clone(), right.clone(), Some("abc"), 0.0);
+
+        assert!(summary.is_empty());
+        assert_eq!(summary.left_row_count, 6);
+        assert_eq!(summary.right_row_count, 6);
+
+        // Without a key, rows are compared by position.
+        let summary = diff(left.clone(), right.clone(), None, 0.0);
+
+        assert_eq!(summary.changed_row_count, 6);
+        assert_eq!(labels(&summary)[0].0, "0");
+
+        right.retain(|value| value.abc != 3);
+        right.push(test_data::simple(100, Some("a"), None));
+        right.push(test_data::simple(1, Some("b"), Some(1.0 / 3.0)));
+
+        // Right-only rows are listed in the order their keys first appear in the right file.
+        let summary = diff(left, right, Some("abc"), 0.0);
+
+        assert_eq!(summary.changed_row_count, 0);
+        assert_eq!(summary.left_only_row_count, 1);
+        assert_eq!(summary.right_only_row_count, 2);
+        assert_eq!(
+            labels(&summary),
+            vec![
+                ("3", vec!["left only"]),
+                ("1", vec!["right only"]),
+                ("100", vec!["right only"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_tolerance() {
+        let left = values();
+        let mut right = left.clone();
+        right[1].vwx = right[1].vwx.map(|vwx| vwx + 1e-9);
+        right[4].yza += 0.25;
+
+        let summary = diff(left.clone(), right.clone(), Some("abc"), 1e-6);
+
+        assert_eq!(summary.changed_row_count, 1);
+        assert_eq!(labels(&summary), vec![("4", vec!["yza"])]);
+
+        let summary = diff(left.clone(), right.clone(), None, 0.5);
+
+        assert!(summary.is_empty());
+
+        let summary = diff(left, right, None, 0.0);
+
+        assert_eq!(summary.changed_row_count, 2);
+        assert_eq!(
+            labels(&summary),
+            vec![("1", vec!["vwx"]), ("4", vec!["yza"])]
+        );
+    }
+}
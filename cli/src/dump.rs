@@ -0,0 +1,244 @@
+use crate::{Error, value};
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::record::Row;
+use parquet::schema::types::Type;
+use parquetry::bloom::RowGroupSubsetReader;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// The debug representation of each field
+    #[default]
+    Debug,
+    /// One JSON object per row
+    Jsonl,
+    /// Comma-separated values with nested groups flattened
+    Csv,
+    /// An aligned table with nested groups flattened
+    Table,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DumpOptions {
+    pub format: Format,
+    /// Dot-separated paths of columns or groups to include
+    pub columns: Option<Vec<String>>,
+    pub row_groups: Option<Vec<usize>>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+pub fn dump<P: AsRef<Path>, W: Write>(
+    input: P,
+    options: &DumpOptions,
+    mut output: W,
+) -> Result<(), Error> {
+    let reader = SerializedFileReader::new(File::open(input)?)?;
+    let schema = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .root_schema()
+        .clone();
+
+    let projection = options
+        .columns
+        .as_ref()
+        .map(|columns| project(&schema, columns))
+        .transpose()?;
+
+    let row_group_count = reader.num_row_groups();
+    let mut row_group_indices = match &options.row_groups {
+        Some(indices) => {
+            if let Some(index) = indices.iter().find(|index| **index >= row_group_count) {
+                return Err(Error::InvalidRowGroup(*index));
+            }
+
+            indices.clone()
+        }
+        None => (0..row_group_count).collect(),
+    };
+
+    // Skip entire row groups where possible instead of reading their rows.
+    let mut skip = options.offset;
+    let mut skipped_row_groups = 0;
+
+    for index in &row_group_indices {
+        let row_count =
+            usize::try_from(reader.metadata().row_group(*index).num_rows()).unwrap_or(0);
+
+        if skip < row_count {
+            break;
+        }
+
+        skip -= row_count;
+        skipped_row_groups += 1;
+    }
+
+    row_group_indices.drain(..skipped_row_groups);
+
+    let output_schema = projection.clone().unwrap_or(schema);
+    let reader = RowGroupSubsetReader::new(reader, row_group_indices);
+    let rows = reader
+        .get_row_iter(projection)?
+        .skip(skip)
+        .take(options.limit.unwrap_or(usize::MAX));
+
+    match options.format {
+        Format::Debug => {
+            for row in rows {
+                for (name, field) in row?.get_column_iter() {
+                    writeln!(output, "{name}: {field:?}")?;
+                }
+            }
+        }
+        Format::Jsonl => {
+            for row in rows {
+                writeln!(output, "{}", value::row_to_json(&row?))?;
+            }
+        }
+        Format::Csv => {
+            let columns = value::flat_columns(&output_schema);
+            let mut writer = csv::Writer::from_writer(output);
+
+            writer.write_record(columns.iter().map(|path| path.join(".")))?;
+
+            for row in rows {
+                writer.write_record(cells(&row?, &columns))?;
+            }
+
+            writer.flush()?;
+        }
+        Format::Table => {
+            let columns = value::flat_columns(&output_schema);
+            let header = columns
+                .iter()
+                .map(|path| path.join("."))
+                .collect::<Vec<_>>();
+            let rows = rows
+                .map(|row| row.map(|row| cells(&row, &columns)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            write_table(&mut output, &header, &rows)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a projection of the schema that includes only the given columns or groups.
+fn project(schema: &Type, columns: &[String]) -> Result<Type, Error> {
+    let paths = columns
+        .iter()
+        .map(|column| column.split('.').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    project_group(schema, &paths, &[])
+}
+
+fn project_group(group: &Type, paths: &[Vec<&str>], prefix: &[&str]) -> Result<Type, Error> {
+    if let Some(path) = paths.iter().find(|path| {
+        !group
+            .get_fields()
+            .iter()
+            .any(|field| field.name() == path[0])
+    }) {
+        return Err(Error::UnknownColumn(
+            [prefix, path.as_slice()].concat().join("."),
+        ));
+    }
+
+    let mut fields = vec![];
+
+    for field in group.get_fields() {
+        let field_paths = paths
+            .iter()
+            .filter(|path| path[0] == field.name())
+            .map(|path| path[1..].to_vec())
+            .collect::<Vec<_>>();
+
+        if field_paths.iter().any(Vec::is_empty) {
+            fields.push(field.clone());
+        } else if !field_paths.is_empty() {
+            let mut field_prefix = prefix.to_vec();
+            field_prefix.push(field.name());
+
+            if field.is_group() {
+                fields.push(Arc::new(project_group(field, &field_paths, &field_prefix)?));
+            } else {
+                return Err(Error::UnknownColumn(
+                    [field_prefix.as_slice(), field_paths[0].as_slice()]
+                        .concat()
+                        .join("."),
+                ));
+            }
+        }
+    }
+
+    let info = group.get_basic_info();
+    let mut builder = Type::group_type_builder(group.name())
+        .with_converted_type(info.converted_type())
+        .with_fields(fields);
+
+    if info.has_repetition() {
+        builder = builder.with_repetition(info.repetition());
+    }
+
+    Ok(builder.build()?)
+}
+
+fn cells(row: &Row, columns: &[Vec<String>]) -> Vec<String> {
+    columns
+        .iter()
+        .map(|path| value::lookup(row, path).map_or_else(String::new, value::field_to_cell))
+        .collect()
+}
+
+fn write_table<W: Write>(
+    output: &mut W,
+    header: &[String],
+    rows: &[Vec<String>],
+) -> Result<(), Error> {
+    let mut widths = header
+        .iter()
+        .map(|cell| cell.chars().count())
+        .collect::<Vec<_>>();
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let write_row = |output: &mut W, row: &[String]| -> std::io::Result<()> {
+        let line = row
+            .iter()
+            .zip(widths.iter().copied())
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        writeln!(output, "{}", line.trim_end())
+    };
+
+    write_row(output, header)?;
+    writeln!(
+        output,
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    )?;
+
+    for row in rows {
+        write_row(output, row)?;
+    }
+
+    Ok(())
+}
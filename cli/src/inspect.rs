@@ -0,0 +1,442 @@
+use crate::Error;
+use parquet::basic::SortOrder;
+use parquet::data_type::AsBytes;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::file::statistics::Statistics;
+use serde::Serialize;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    #[default]
+    Human,
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub version: i32,
+    pub created_by: Option<String>,
+    pub num_rows: i64,
+    pub key_value_metadata: Vec<KeyValue>,
+    pub row_groups: Vec<RowGroup>,
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RowGroup {
+    pub num_rows: i64,
+    pub total_byte_size: i64,
+    pub compressed_size: i64,
+    pub sorting_columns: Option<Vec<SortingColumn>>,
+    pub columns: Vec<ColumnChunk>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SortingColumn {
+    pub column: String,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnChunk {
+    pub path: String,
+    pub physical_type: String,
+    pub compression: String,
+    pub encodings: Vec<String>,
+    pub num_values: i64,
+    pub compressed_size: i64,
+    pub uncompressed_size: i64,
+    pub statistics: Option<ColumnStatistics>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnStatistics {
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub null_count: Option<u64>,
+    pub distinct_count: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Warning {
+    /// The column chunk has no statistics, or no min and max values although it contains non-null values.
+    MissingStatistics { row_group: usize, column: String },
+    /// The leading sorting column's values in the row group are out of order with respect to the previous row group.
+    UnsortedRowGroup { row_group: usize, column: String },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingStatistics { row_group, column } => {
+                write!(f, "row group {row_group}: missing statistics for {column}")
+            }
+            Self::UnsortedRowGroup { row_group, column } => write!(
+                f,
+                "row group {row_group}: values of sorting column {column} are out of order with the previous row group"
+            ),
+        }
+    }
+}
+
+pub fn inspect<P: AsRef<Path>>(input: P) -> Result<Report, Error> {
+    let reader = SerializedFileReader::new(File::open(input)?)?;
+
+    Ok(Report::new(reader.metadata()))
+}
+
+impl Report {
+    #[must_use]
+    pub fn new(metadata: &ParquetMetaData) -> Self {
+        let file_metadata = metadata.file_metadata();
+        let schema = file_metadata.schema_descr();
+
+        let row_groups = metadata
+            .row_groups()
+            .iter()
+            .map(|row_group| RowGroup {
+                num_rows: row_group.num_rows(),
+                total_byte_size: row_group.total_byte_size(),
+                compressed_size: row_group.compressed_size(),
+                sorting_columns: row_group.sorting_columns().map(|sorting_columns| {
+                    sorting_columns
+                        .iter()
+                        .map(|sorting_column| SortingColumn {
+                            column: usize::try_from(sorting_column.column_idx)
+                                .ok()
+                                .filter(|index| *index < schema.num_columns())
+                                .map_or_else(
+                                    || sorting_column.column_idx.to_string(),
+                                    |index| schema.column(index).path().string(),
+                                ),
+                            descending: sorting_column.descending,
+                            nulls_first: sorting_column.nulls_first,
+                        })
+                        .collect()
+                }),
+                columns: row_group.columns().iter().map(ColumnChunk::new).collect(),
+            })
+            .collect();
+
+        Self {
+            version: file_metadata.version(),
+            created_by: file_metadata.created_by().map(str::to_string),
+            num_rows: file_metadata.num_rows(),
+            key_value_metadata: file_metadata
+                .key_value_metadata()
+                .map(|key_value_metadata| {
+                    key_value_metadata
+                        .iter()
+                        .map(|key_value| KeyValue {
+                            key: key_value.key.clone(),
+                            value: key_value.value.clone(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            row_groups,
+            warnings: warnings(metadata),
+        }
+    }
+
+    pub fn write<W: Write>(&self, output: &mut W, format: Format) -> Result<(), Error> {
+        match format {
+            Format::Human => self.write_human(output)?,
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut *output, self)?;
+                writeln!(output)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_human<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        writeln!(output, "version: {}", self.version)?;
+
+        if let Some(created_by) = &self.created_by {
+            writeln!(output, "created by: {created_by}")?;
+        }
+
+        writeln!(output, "rows: {}", self.num_rows)?;
+
+        if !self.key_value_metadata.is_empty() {
+            writeln!(output, "key-value metadata:")?;
+
+            for key_value in &self.key_value_metadata {
+                writeln!(
+                    output,
+                    "  {}: {}",
+                    key_value.key,
+                    key_value.value.as_deref().unwrap_or("")
+                )?;
+            }
+        }
+
+        for (index, row_group) in self.row_groups.iter().enumerate() {
+            writeln!(
+                output,
+                "row group {index}: {} rows, {} bytes ({} compressed)",
+                row_group.num_rows, row_group.total_byte_size, row_group.compressed_size
+            )?;
+
+            if let Some(sorting_columns) = &row_group.sorting_columns {
+                let sorting_columns = sorting_columns
+                    .iter()
+                    .map(|sorting_column| {
+                        format!(
+                            "{}{}{}",
+                            sorting_column.column,
+                            if sorting_column.descending {
+                                " desc"
+                            } else {
+                                ""
+                            },
+                            if sorting_column.nulls_first {
+                                " nulls first"
+                            } else {
+                                ""
+                            }
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                writeln!(output, "  sorted by: {}", sorting_columns.join(", "))?;
+            }
+
+            for column in &row_group.columns {
+                writeln!(
+                    output,
+                    "  {} ({}): {} values, {} bytes ({} compressed, {}), encodings: {}",
+                    column.path,
+                    column.physical_type,
+                    column.num_values,
+                    column.uncompressed_size,
+                    column.compressed_size,
+                    column.compression,
+                    column.encodings.join(", ")
+                )?;
+
+                match &column.statistics {
+                    Some(statistics) => writeln!(
+                        output,
+                        "    min: {}, max: {}, nulls: {}, distinct: {}",
+                        display_option(statistics.min.as_ref()),
+                        display_option(statistics.max.as_ref()),
+                        display_option(statistics.null_count.as_ref()),
+                        display_option(statistics.distinct_count.as_ref())
+                    )?,
+                    None => writeln!(output, "    no statistics")?,
+                }
+            }
+        }
+
+        for warning in &self.warnings {
+            writeln!(output, "warning: {warning}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ColumnChunk {
+    fn new(column: &ColumnChunkMetaData) -> Self {
+        let sort_order = column.column_descr().sort_order();
+
+        Self {
+            path: column.column_path().string(),
+            physical_type: column.column_type().to_string(),
+            compression: column.compression().to_string(),
+            encodings: column
+                .encodings()
+                .into_iter()
+                .map(|encoding| encoding.to_string())
+                .collect(),
+            num_values: column.num_values(),
+            compressed_size: column.compressed_size(),
+            uncompressed_size: column.uncompressed_size(),
+            statistics: column.statistics().map(|statistics| ColumnStatistics {
+                min: StatisticsBound::new(statistics, sort_order, false)
+                    .map(|bound| bound.to_string()),
+                max: StatisticsBound::new(statistics, sort_order, true)
+                    .map(|bound| bound.to_string()),
+                null_count: statistics.null_count_opt(),
+                distinct_count: statistics.distinct_count_opt(),
+            }),
+        }
+    }
+}
+
+/// A min or max statistics value that can be compared according to the column's sort order.
+#[derive(Debug, PartialEq, PartialOrd)]
+enum StatisticsBound {
+    Boolean(bool),
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    Bytes(Vec<u8>),
+}
+
+impl StatisticsBound {
+    fn new(statistics: &Statistics, sort_order: SortOrder, max: bool) -> Option<Self> {
+        let unsigned = sort_order == SortOrder::UNSIGNED;
+
+        match statistics {
+            Statistics::Boolean(statistics) => {
+                pick(statistics.min_opt(), statistics.max_opt(), max)
+                    .map(|value| Self::Boolean(*value))
+            }
+            Statistics::Int32(statistics) => pick(statistics.min_opt(), statistics.max_opt(), max)
+                .map(|value| {
+                    if unsigned {
+                        Self::Unsigned(u32::from_ne_bytes(value.to_ne_bytes()).into())
+                    } else {
+                        Self::Signed((*value).into())
+                    }
+                }),
+            Statistics::Int64(statistics) => pick(statistics.min_opt(), statistics.max_opt(), max)
+                .map(|value| {
+                    if unsigned {
+                        Self::Unsigned(u64::from_ne_bytes(value.to_ne_bytes()))
+                    } else {
+                        Self::Signed(*value)
+                    }
+                }),
+            Statistics::Int96(_) => None,
+            Statistics::Float(statistics) => pick(statistics.min_opt(), statistics.max_opt(), max)
+                .map(|value| Self::Float((*value).into())),
+            Statistics::Double(statistics) => pick(statistics.min_opt(), statistics.max_opt(), max)
+                .map(|value| Self::Float(*value)),
+            Statistics::ByteArray(statistics) => {
+                pick(statistics.min_opt(), statistics.max_opt(), max)
+                    .map(|value| Self::Bytes(value.as_bytes().to_vec()))
+            }
+            Statistics::FixedLenByteArray(statistics) => {
+                pick(statistics.min_opt(), statistics.max_opt(), max)
+                    .map(|value| Self::Bytes(value.as_bytes().to_vec()))
+            }
+        }
+    }
+}
+
+impl Display for StatisticsBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Signed(value) => write!(f, "{value}"),
+            Self::Unsigned(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Bytes(value) => match std::str::from_utf8(value) {
+                Ok(value) => write!(f, "{value:?}"),
+                Err(_) => {
+                    write!(f, "0x")?;
+
+                    for byte in value {
+                        write!(f, "{byte:02x}")?;
+                    }
+
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+fn warnings(metadata: &ParquetMetaData) -> Vec<Warning> {
+    let mut warnings = vec![];
+
+    for (row_group_index, row_group) in metadata.row_groups().iter().enumerate() {
+        for column in row_group.columns() {
+            let has_bounds = column.statistics().is_some_and(|statistics| {
+                statistics.min_bytes_opt().is_some()
+                    || statistics.null_count_opt() == u64::try_from(column.num_values()).ok()
+            });
+
+            if !has_bounds {
+                warnings.push(Warning::MissingStatistics {
+                    row_group: row_group_index,
+                    column: column.column_path().string(),
+                });
+            }
+        }
+    }
+
+    // Statistics only allow us to check the leading sorting column at row group boundaries.
+    for (row_group_index, pair) in metadata.row_groups().windows(2).enumerate() {
+        let (previous, next) = (&pair[0], &pair[1]);
+
+        let Some(sorting_column) = next
+            .sorting_columns()
+            .and_then(|sorting_columns| sorting_columns.first())
+        else {
+            continue;
+        };
+
+        if previous
+            .sorting_columns()
+            .and_then(|sorting_columns| sorting_columns.first())
+            != Some(sorting_column)
+        {
+            continue;
+        }
+
+        let Ok(column_index) = usize::try_from(sorting_column.column_idx) else {
+            continue;
+        };
+
+        let (Some(previous_column), Some(next_column)) = (
+            previous.columns().get(column_index),
+            next.columns().get(column_index),
+        ) else {
+            continue;
+        };
+
+        let sort_order = next_column.column_descr().sort_order();
+
+        let bounds = previous_column
+            .statistics()
+            .zip(next_column.statistics())
+            .and_then(|(previous_statistics, next_statistics)| {
+                if sorting_column.descending {
+                    StatisticsBound::new(previous_statistics, sort_order, false)
+                        .zip(StatisticsBound::new(next_statistics, sort_order, true))
+                        .map(|(previous_min, next_max)| previous_min < next_max)
+                } else {
+                    StatisticsBound::new(previous_statistics, sort_order, true)
+                        .zip(StatisticsBound::new(next_statistics, sort_order, false))
+                        .map(|(previous_max, next_min)| previous_max > next_min)
+                }
+            });
+
+        if bounds == Some(true) {
+            warnings.push(Warning::UnsortedRowGroup {
+                row_group: row_group_index + 1,
+                column: next_column.column_path().string(),
+            });
+        }
+    }
+
+    warnings
+}
+
+fn pick<T>(min: Option<T>, max: Option<T>, is_max: bool) -> Option<T> {
+    if is_max { max } else { min }
+}
+
+fn display_option<T: Display>(value: Option<&T>) -> String {
+    value.map_or_else(|| "none".to_string(), ToString::to_string)
+}
@@ -2,30 +2,209 @@
 #![allow(clippy::missing_errors_doc)]
 #![forbid(unsafe_code)]
 use cli_helpers::prelude::*;
-use std::{fs::File, path::PathBuf};
+use std::path::PathBuf;
+
+mod check;
+mod column;
+mod config;
+mod convert;
+mod diff;
+mod dump;
+mod inspect;
+mod merge;
+mod schema;
+mod sort;
+mod split;
+#[cfg(test)]
+mod test_data;
+mod validate;
+mod value;
 
 fn main() -> Result<(), Error> {
     let opts: Opts = Opts::parse();
     opts.verbose.init_logging()?;
 
     match opts.command {
-        Command::Gen { source } => {
-            let schema = parquetry_gen::ParsedFileSchema::open(source, Default::default())?;
-            println!("{}", schema.code()?);
-        }
-        Command::Dump { input } => {
-            let reader =
-                parquet::file::serialized_reader::SerializedFileReader::new(File::open(input)?)?;
-            for row in reader {
-                let row = row?;
-                for (name, field) in row.get_column_iter() {
-                    println!("{name}: {field:?}");
+        Command::Gen {
+            source,
+            output,
+            suffix,
+            config,
+        } => {
+            let config = config.config();
+
+            if source.is_dir() {
+                let output = output.ok_or(Error::MissingOutputDirectory)?;
+                std::fs::create_dir_all(&output)?;
+
+                for schema in
+                    parquetry_gen::ParsedFileSchema::open_dir(source, config, Some(&suffix))?
+                {
+                    std::fs::write(output.join(format!("{}.rs", schema.name)), schema.code()?)?;
                 }
+            } else {
+                let schema = parquetry_gen::ParsedFileSchema::open(source, config)?;
+
+                match output {
+                    Some(output) if output.is_dir() => {
+                        std::fs::write(output.join(format!("{}.rs", schema.name)), schema.code()?)?;
+                    }
+                    Some(output) => {
+                        std::fs::write(output, schema.code()?)?;
+                    }
+                    None => {
+                        println!("{}", schema.code()?);
+                    }
+                }
+            }
+        }
+        Command::Check {
+            schemas,
+            generated,
+            suffix,
+            config,
+        } => {
+            let outdated = check::check(schemas, generated, &suffix, config.config())?;
+
+            if !outdated.is_empty() {
+                return Err(Error::OutdatedCode(outdated));
+            }
+        }
+        Command::Dump {
+            input,
+            format,
+            columns,
+            row_groups,
+            offset,
+            limit,
+        } => {
+            let options = dump::DumpOptions {
+                format,
+                columns,
+                row_groups,
+                offset,
+                limit,
+            };
+
+            dump::dump(input, &options, std::io::stdout().lock())?;
+        }
+        Command::Inspect { input, format } => {
+            inspect::inspect(input)?.write(&mut std::io::stdout().lock(), format)?;
+        }
+        Command::Validate {
+            schema,
+            input,
+            rows,
+        } => {
+            let schema = parquetry_gen::ParsedFileSchema::open(schema, Default::default())?;
+            let violations = validate::validate(&schema, input, rows)?;
+
+            for violation in &violations {
+                println!("{violation}");
+            }
+
+            if !violations.is_empty() {
+                return Err(Error::InvalidFile(violations.len()));
             }
         }
-        Command::DumpSchema { source } => {
-            let schema = parquetry_gen::ParsedFileSchema::open(source, Default::default())?;
-            println!("{:?}", schema.schema);
+        Command::Convert {
+            schema,
+            input,
+            output,
+            format,
+            row_group_size,
+            compression,
+        } => {
+            let options = convert::ConvertOptions {
+                format,
+                row_group_size,
+                compression,
+            };
+
+            convert::convert(schema, input, output, &options)?;
+        }
+        Command::Sort {
+            input,
+            by,
+            output,
+            row_group_size,
+            compression,
+            backend,
+            keep,
+            memory_budget,
+            temp_dir,
+        } => {
+            let options = sort::SortOptions {
+                by,
+                row_group_size,
+                compression,
+                backend,
+                keep,
+                memory_budget,
+                temp_dir,
+            };
+
+            sort::sort(input, output, &options)?;
+        }
+        Command::Diff {
+            left,
+            right,
+            key,
+            tolerance,
+            max_rows,
+        } => {
+            let options = diff::DiffOptions {
+                key,
+                tolerance,
+                max_rows,
+            };
+
+            let summary = diff::diff(left, right, &options)?;
+            summary.write(&mut std::io::stdout().lock())?;
+
+            if !summary.is_empty() {
+                return Err(Error::FilesDiffer);
+            }
+        }
+        Command::Merge {
+            inputs,
+            output,
+            compression,
+        } => {
+            let options = merge::MergeOptions { compression };
+
+            merge::merge(&inputs, output, &options)?;
+        }
+        Command::Split {
+            input,
+            output_dir,
+            max_row_groups,
+            max_rows,
+            compression,
+        } => {
+            let options = split::SplitOptions {
+                max_row_groups,
+                max_rows,
+                compression,
+            };
+
+            std::fs::create_dir_all(&output_dir)?;
+
+            for path in split::split(input, output_dir, &options)? {
+                println!("{}", path.display());
+            }
+        }
+        Command::Schema {
+            input,
+            format,
+            config,
+        } => {
+            schema::schema(
+                input,
+                format,
+                config.config(),
+                &mut std::io::stdout().lock(),
+            )?;
         }
     }
 
@@ -40,8 +219,42 @@ pub enum Error {
     Parquet(#[from] parquet::errors::ParquetError),
     #[error("Code generation error")]
     Parquetry(#[from] parquetry_gen::error::Error),
+    #[error("Sort error")]
+    Sort(#[from] parquetry_sort::Error),
+    #[error("CSV error")]
+    Csv(#[from] csv::Error),
+    #[error("JSON error")]
+    Json(#[from] serde_json::Error),
     #[error("CLI argument reading error")]
     Args(#[from] cli_helpers::Error),
+    #[error("Output directory is required for a schema directory")]
+    MissingOutputDirectory,
+    #[error("Generated code is out of date")]
+    OutdatedCode(Vec<PathBuf>),
+    #[error("Unknown column")]
+    UnknownColumn(String),
+    #[error("Unsupported sort column")]
+    UnsupportedSortColumn(String),
+    #[error("Invalid row group index")]
+    InvalidRowGroup(usize),
+    #[error("File does not match schema")]
+    InvalidFile(usize),
+    #[error("Files differ")]
+    FilesDiffer,
+    #[error("At least one input file is required")]
+    MissingInput,
+    #[error("Input schemas differ")]
+    SchemaMismatch(PathBuf),
+    #[error("Invalid input value")]
+    InvalidInput {
+        row_index: usize,
+        path: String,
+        message: String,
+    },
+    #[error("Invalid encoded row")]
+    InvalidEncodedRow,
+    #[error("Unsupported column type")]
+    UnsupportedColumnType(String),
 }
 
 #[derive(Debug, Parser)]
@@ -55,16 +268,170 @@ struct Opts {
 
 #[derive(Debug, Parser)]
 enum Command {
+    /// Generate code for a schema file or directory
     Gen {
+        /// Schema file, or directory of schema files
         #[clap(long)]
         source: PathBuf,
+        /// Output file or directory (generated code is printed if this is not provided for a single schema)
+        #[clap(long)]
+        output: Option<PathBuf>,
+        /// Only generate code for files in the schema directory with this suffix
+        #[clap(long, default_value = ".parquet.txt")]
+        suffix: String,
+        #[clap(flatten)]
+        config: config::ConfigOpts,
+    },
+    /// Check that generated code is up to date with its schemas
+    Check {
+        /// Directory of schema files
+        #[clap(long)]
+        schemas: PathBuf,
+        /// Directory of generated code
+        #[clap(long)]
+        generated: PathBuf,
+        /// Only check files in the schema directory with this suffix
+        #[clap(long, default_value = ".parquet.txt")]
+        suffix: String,
+        #[clap(flatten)]
+        config: config::ConfigOpts,
     },
+    /// Print the rows of a Parquet file
     Dump {
         #[clap(long)]
         input: PathBuf,
+        #[clap(long, value_enum, default_value_t)]
+        format: dump::Format,
+        /// Columns or groups to include (comma-separated, with dot-separated paths)
+        #[clap(long, value_delimiter = ',')]
+        columns: Option<Vec<String>>,
+        /// Row groups to include (comma-separated indices)
+        #[clap(long, value_delimiter = ',')]
+        row_groups: Option<Vec<usize>>,
+        /// Number of rows to skip
+        #[clap(long, default_value_t = 0)]
+        offset: usize,
+        /// Maximum number of rows to print
+        #[clap(long)]
+        limit: Option<usize>,
     },
-    DumpSchema {
+    /// Print file metadata, statistics, and encodings
+    Inspect {
         #[clap(long)]
-        source: PathBuf,
+        input: PathBuf,
+        #[clap(long, value_enum, default_value_t)]
+        format: inspect::Format,
+    },
+    /// Check that a file matches a schema definition
+    Validate {
+        /// Schema file
+        #[clap(long)]
+        schema: PathBuf,
+        #[clap(long)]
+        input: PathBuf,
+        /// Also check that every row can be read by the generated code
+        #[clap(long)]
+        rows: bool,
+    },
+    /// Convert JSON Lines or CSV to Parquet with a schema definition
+    Convert {
+        /// Schema file
+        #[clap(long)]
+        schema: PathBuf,
+        #[clap(long)]
+        input: PathBuf,
+        #[clap(long)]
+        output: PathBuf,
+        /// Input format (inferred from the file extension by default)
+        #[clap(long, value_enum)]
+        format: Option<convert::InputFormat>,
+        /// Maximum number of rows per row group
+        #[clap(long, default_value_t = 1024 * 1024)]
+        row_group_size: usize,
+        /// Compression codec (e.g. "snappy" or "zstd(3)")
+        #[clap(long, default_value = "uncompressed")]
+        compression: parquet::basic::Compression,
+    },
+    /// Sort the rows of a file by one or more columns
+    Sort {
+        #[clap(long)]
+        input: PathBuf,
+        /// Column paths to sort by (comma-separated, with a "-" prefix for descending order and a ":nulls-first"
+        /// suffix to sort null values before non-null values)
+        #[clap(long, value_delimiter = ',', required = true)]
+        by: Vec<String>,
+        #[clap(long)]
+        output: PathBuf,
+        /// Maximum number of rows per row group
+        #[clap(long, default_value_t = 1024 * 1024)]
+        row_group_size: usize,
+        /// Compression codec (e.g. "snappy" or "zstd(3)")
+        #[clap(long, default_value = "uncompressed")]
+        compression: parquet::basic::Compression,
+        /// Storage for sorting
+        #[clap(long, value_enum, default_value_t)]
+        backend: sort::SortBackend,
+        /// Rows to keep when several rows have the same sort key
+        #[clap(long, value_enum, default_value_t)]
+        keep: sort::Keep,
+        /// Number of bytes to buffer in memory before spilling a sorted run (for the external backend)
+        #[clap(long)]
+        memory_budget: Option<usize>,
+        /// Directory for the temporary sort database or sorted runs
+        #[clap(long)]
+        temp_dir: Option<PathBuf>,
+    },
+    /// Compare the schemas and rows of two files
+    Diff {
+        left: PathBuf,
+        right: PathBuf,
+        /// Dot-separated path of a column to match rows by (rows are matched by position by default)
+        #[clap(long)]
+        key: Option<String>,
+        /// Maximum absolute difference between floating point values that are considered equal
+        #[clap(long, default_value_t = 0.0)]
+        tolerance: f64,
+        /// Maximum number of differing rows to print
+        #[clap(long, default_value_t = 10)]
+        max_rows: usize,
+    },
+    /// Concatenate the row groups of files with identical schemas
+    Merge {
+        #[clap(required = true)]
+        inputs: Vec<PathBuf>,
+        #[clap(long)]
+        output: PathBuf,
+        /// Compression codec (e.g. "snappy" or "zstd(3)"), where column chunks are re-encoded if they don't match
+        /// (the codec of the first input is used by default)
+        #[clap(long)]
+        compression: Option<parquet::basic::Compression>,
+    },
+    /// Split a file into pieces with bounded numbers of row groups or rows
+    Split {
+        #[clap(long)]
+        input: PathBuf,
+        #[clap(long)]
+        output_dir: PathBuf,
+        /// Maximum number of row groups per output file
+        #[clap(long, required_unless_present = "max_rows")]
+        max_row_groups: Option<usize>,
+        /// Maximum number of rows per output file
+        #[clap(long)]
+        max_rows: Option<usize>,
+        /// Compression codec (e.g. "snappy" or "zstd(3)"), where column chunks are re-encoded if they don't match
+        /// (the codec of the input is used by default)
+        #[clap(long)]
+        compression: Option<parquet::basic::Compression>,
+    },
+    /// Print the schema of a Parquet file or schema file
+    #[clap(alias = "dump-schema")]
+    Schema {
+        /// Parquet file or schema file
+        #[clap(long, alias = "source")]
+        input: PathBuf,
+        #[clap(long, value_enum, default_value_t)]
+        format: schema::Format,
+        #[clap(flatten)]
+        config: config::ConfigOpts,
     },
 }
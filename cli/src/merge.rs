@@ -0,0 +1,485 @@
+use crate::{Error, column::ColumnData, validate};
+use parquet::basic::{Compression, Type as PhysicalType};
+use parquet::bloom_filter::Sbbf;
+use parquet::column::reader::ColumnReader;
+use parquet::column::writer::ColumnCloseResult;
+use parquet::file::metadata::{
+    ColumnChunkMetaData, KeyValue, PageIndexPolicy, ParquetMetaData, ParquetMetaDataReader,
+};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::{ColumnDescPtr, SchemaDescriptor};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const BATCH_SIZE: usize = 10_000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MergeOptions {
+    /// The output compression codec (the codec of the first input is used if this is not provided)
+    pub compression: Option<Compression>,
+}
+
+/// Concatenate the row groups of files with identical schemas, returning the number of rows written.
+///
+/// Column chunks are copied without re-encoding when their compression matches the output's, and are otherwise
+/// re-encoded column by column (see `Input::row_group_columns`). Key-value metadata from all inputs is preserved (the
+/// first value is used for duplicate keys).
+pub fn merge<I: AsRef<Path>, O: AsRef<Path>>(
+    inputs: &[I],
+    output: O,
+    options: &MergeOptions,
+) -> Result<usize, Error> {
+    let inputs = inputs
+        .iter()
+        .map(Input::open)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some((first, rest)) = inputs.split_first() else {
+        return Err(Error::MissingInput);
+    };
+
+    for input in rest {
+        if !validate::compare_schemas(
+            first.descriptor().root_schema(),
+            input.descriptor().root_schema(),
+        )
+        .is_empty()
+        {
+            return Err(Error::SchemaMismatch(input.path.clone()));
+        }
+    }
+
+    let mut key_value_metadata: Vec<KeyValue> = vec![];
+
+    for input in &inputs {
+        for entry in input
+            .metadata
+            .file_metadata()
+            .key_value_metadata()
+            .into_iter()
+            .flatten()
+        {
+            if !key_value_metadata
+                .iter()
+                .any(|existing| existing.key == entry.key)
+            {
+                key_value_metadata.push(entry.clone());
+            }
+        }
+    }
+
+    let mut output = Output::create(
+        output,
+        first.descriptor(),
+        options
+            .compression
+            .unwrap_or_else(|| first.default_compression()),
+        (!key_value_metadata.is_empty()).then_some(key_value_metadata),
+    )?;
+
+    for input in &inputs {
+        for index in 0..input.metadata.num_row_groups() {
+            output.append_row_group(input, index)?;
+        }
+    }
+
+    output.close()
+}
+
+/// An input file, with its metadata (including page indexes, if available).
+pub struct Input {
+    pub path: PathBuf,
+    file: File,
+    metadata: ParquetMetaData,
+    reader: SerializedFileReader<File>,
+}
+
+impl Input {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        let metadata = ParquetMetaDataReader::new()
+            .with_page_index_policy(PageIndexPolicy::Optional)
+            .parse_and_finish(&file)?;
+        let reader = SerializedFileReader::new(file.try_clone()?)?;
+
+        Ok(Self {
+            path,
+            file,
+            metadata,
+            reader,
+        })
+    }
+
+    pub const fn metadata(&self) -> &ParquetMetaData {
+        &self.metadata
+    }
+
+    pub fn descriptor(&self) -> &SchemaDescriptor {
+        self.metadata.file_metadata().schema_descr()
+    }
+
+    /// The compression codec of the first column chunk (or no compression if the file has no row groups).
+    pub fn default_compression(&self) -> Compression {
+        self.metadata
+            .row_groups()
+            .first()
+            .and_then(|row_group| row_group.columns().first())
+            .map_or(Compression::UNCOMPRESSED, ColumnChunkMetaData::compression)
+    }
+
+    /// Open the columns of a row group for re-encoding.
+    ///
+    /// Values are read and written with their physical types, so re-encoding is lossless, but `INT96` columns are not
+    /// supported.
+    pub fn row_group_columns(&self, index: usize) -> Result<RowGroupColumns, Error> {
+        let row_group = self.reader.get_row_group(index)?;
+        let columns = self
+            .descriptor()
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(column_index, descriptor)| {
+                if descriptor.physical_type() == PhysicalType::INT96 {
+                    Err(Error::UnsupportedColumnType(descriptor.path().string()))
+                } else {
+                    Ok((
+                        descriptor.clone(),
+                        row_group.get_column_reader(column_index)?,
+                        ColumnData::new(descriptor.physical_type()),
+                    ))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(RowGroupColumns { columns })
+    }
+
+    fn can_copy(&self, index: usize, compression: Compression) -> bool {
+        self.metadata
+            .row_group(index)
+            .columns()
+            .iter()
+            .all(|column| column.compression() == compression)
+    }
+}
+
+/// The columns of an input row group, which are read in batches as rows are re-encoded.
+pub struct RowGroupColumns {
+    columns: Vec<(ColumnDescPtr, ColumnReader, ColumnData)>,
+}
+
+/// An output file that row groups are copied into when possible, and re-encoded into otherwise.
+pub struct Output {
+    writer: SerializedFileWriter<File>,
+    compression: Compression,
+    row_count: usize,
+}
+
+impl Output {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        descriptor: &SchemaDescriptor,
+        compression: Compression,
+        key_value_metadata: Option<Vec<KeyValue>>,
+    ) -> Result<Self, Error> {
+        let properties = WriterProperties::builder()
+            .set_compression(compression)
+            .set_key_value_metadata(key_value_metadata)
+            .build();
+
+        let writer = SerializedFileWriter::new(
+            File::create(path)?,
+            descriptor.root_schema_ptr(),
+            Arc::new(properties),
+        )?;
+
+        Ok(Self {
+            writer,
+            compression,
+            row_count: 0,
+        })
+    }
+
+    /// The number of rows written.
+    pub const fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// The number of row groups written.
+    pub fn row_group_count(&self) -> usize {
+        self.writer.flushed_row_groups().len()
+    }
+
+    /// Append a row group from the input, copying its column chunks if their compression matches.
+    pub fn append_row_group(&mut self, input: &Input, index: usize) -> Result<(), Error> {
+        let row_group = input.metadata.row_group(index);
+
+        if input.can_copy(index, self.compression) {
+            let column_indexes = input
+                .metadata
+                .column_index()
+                .and_then(|column_indexes| column_indexes.get(index));
+            let offset_indexes = input
+                .metadata
+                .offset_index()
+                .and_then(|offset_indexes| offset_indexes.get(index));
+
+            let mut row_group_writer = self.writer.next_row_group()?;
+
+            for (column_index, column) in row_group.columns().iter().enumerate() {
+                let result = ColumnCloseResult {
+                    bytes_written: column.compressed_size().try_into().unwrap_or_default(),
+                    rows_written: row_group.num_rows().try_into().unwrap_or_default(),
+                    metadata: column.clone(),
+                    bloom_filter: Sbbf::read_from_column_chunk(column, &input.file)?,
+                    column_index: column_indexes
+                        .and_then(|column_indexes| column_indexes.get(column_index))
+                        .cloned(),
+                    offset_index: offset_indexes
+                        .and_then(|offset_indexes| offset_indexes.get(column_index))
+                        .cloned(),
+                };
+
+                row_group_writer.append_column(&input.file, result)?;
+            }
+
+            row_group_writer.close()?;
+            self.row_count += usize::try_from(row_group.num_rows()).unwrap_or_default();
+        } else {
+            let row_count = usize::try_from(row_group.num_rows()).unwrap_or_default();
+
+            self.append_rows(&mut input.row_group_columns(index)?, row_count)?;
+        }
+
+        Ok(())
+    }
+
+    /// Re-encode up to the given number of rows from the columns as a new row group, returning the number of rows
+    /// written.
+    ///
+    /// Each column is read and written in batches, so only the row group being written is held in memory.
+    pub fn append_rows(
+        &mut self,
+        columns: &mut RowGroupColumns,
+        row_count: usize,
+    ) -> Result<usize, Error> {
+        let mut row_group_writer = self.writer.next_row_group()?;
+        let mut rows_written = 0;
+
+        for (descriptor, reader, buffer) in &mut columns.columns {
+            let mut column_writer = row_group_writer
+                .next_column()?
+                .ok_or_else(|| Error::UnknownColumn(descriptor.path().string()))?;
+
+            rows_written = 0;
+
+            while rows_written < row_count {
+                let records_read = buffer.read_records(
+                    reader,
+                    descriptor,
+                    BATCH_SIZE.min(row_count - rows_written),
+                )?;
+
+                if records_read == 0 {
+                    break;
+                }
+
+                buffer.write(&mut column_writer, descriptor)?;
+                rows_written += records_read;
+            }
+
+            column_writer.close()?;
+        }
+
+        row_group_writer.close()?;
+        self.row_count += rows_written;
+
+        Ok(rows_written)
+    }
+
+    /// Close the file, returning the number of rows written.
+    pub fn close(self) -> Result<usize, Error> {
+        self.writer.close()?;
+
+        Ok(self.row_count)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MergeOptions;
+    use crate::{Error, test_data};
+    use parquet::basic::Compression;
+    use parquet::data_type::{Int96, Int96Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::reader::FileReader;
+    use parquet::file::serialized_reader::SerializedFileReader;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::fs::File;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    /// The compression codec and compressed size of each column chunk in each row group.
+    fn column_chunks<P: AsRef<Path>>(path: P) -> Vec<Vec<(Compression, i64)>> {
+        let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+
+        reader
+            .metadata()
+            .row_groups()
+            .iter()
+            .map(|row_group| {
+                row_group
+                    .columns()
+                    .iter()
+                    .map(|column| (column.compression(), column.compressed_size()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merge_copy_and_re_encode() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let snappy = temp_dir.path().join("snappy.parquet");
+        let uncompressed = temp_dir.path().join("uncompressed.parquet");
+        let values = test_data::simple_values();
+
+        test_data::write_simple(
+            &snappy,
+            vec![values[..3].to_vec(), values[3..].to_vec()],
+            Compression::SNAPPY,
+        );
+        test_data::write_simple(
+            &uncompressed,
+            vec![values[5..].to_vec()],
+            Compression::UNCOMPRESSED,
+        );
+
+        let mut expected = values.clone();
+        expected.extend(values[5..].to_vec());
+
+        // The first input's row groups are copied, and the second input's are re-encoded.
+        let output = temp_dir.path().join("output.parquet");
+        let row_count = super::merge(
+            &[&snappy, &uncompressed],
+            &output,
+            &MergeOptions { compression: None },
+        )
+        .unwrap();
+
+        let snappy_chunks = column_chunks(&snappy);
+        let output_chunks = column_chunks(&output);
+
+        assert_eq!(row_count, expected.len());
+        test_data::assert_same(&test_data::read_simple(&output), &expected);
+        assert_eq!(output_chunks.len(), 3);
+        assert_eq!(output_chunks[..2], snappy_chunks[..]);
+        assert!(
+            output_chunks[2]
+                .iter()
+                .all(|(compression, _)| *compression == Compression::SNAPPY)
+        );
+
+        // Every row group is re-encoded when the compression changes.
+        let row_count = super::merge(
+            &[&snappy, &uncompressed],
+            &output,
+            &MergeOptions {
+                compression: Some(Compression::UNCOMPRESSED),
+            },
+        )
+        .unwrap();
+
+        let uncompressed_chunks = column_chunks(&uncompressed);
+        let output_chunks = column_chunks(&output);
+
+        assert_eq!(row_count, expected.len());
+        test_data::assert_same(&test_data::read_simple(&output), &expected);
+        assert_eq!(output_chunks.len(), 3);
+        assert_eq!(output_chunks[2..], uncompressed_chunks[..]);
+        assert!(
+            output_chunks
+                .iter()
+                .flatten()
+                .all(|(compression, _)| { *compression == Compression::UNCOMPRESSED })
+        );
+    }
+
+    #[test]
+    fn merge_schema_mismatch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let simple = temp_dir.path().join("simple.parquet");
+        let int96 = temp_dir.path().join("int96.parquet");
+
+        test_data::write_simple(
+            &simple,
+            vec![test_data::simple_values()],
+            Compression::UNCOMPRESSED,
+        );
+        write_int96(&int96, Compression::UNCOMPRESSED);
+
+        let result = super::merge(
+            &[&simple, &int96],
+            temp_dir.path().join("output.parquet"),
+            &MergeOptions { compression: None },
+        );
+
+        assert!(matches!(result, Err(Error::SchemaMismatch(path)) if path == int96));
+    }
+
+    #[test]
+    fn merge_int96() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("input.parquet");
+        let output = temp_dir.path().join("output.parquet");
+
+        write_int96(&input, Compression::UNCOMPRESSED);
+
+        // `INT96` column chunks can be copied, but not re-encoded.
+        let row_count =
+            super::merge(&[&input], &output, &MergeOptions { compression: None }).unwrap();
+
+        assert_eq!(row_count, 2);
+
+        let result = super::merge(
+            &[&input],
+            &output,
+            &MergeOptions {
+                compression: Some(Compression::SNAPPY),
+            },
+        );
+
+        assert!(matches!(result, Err(Error::UnsupportedColumnType(path)) if path == "timestamp"));
+    }
+
+    fn write_int96<P: AsRef<Path>>(path: P, compression: Compression) {
+        let schema = parse_message_type("message int96 { required int96 timestamp; }").unwrap();
+        let properties = WriterProperties::builder()
+            .set_compression(compression)
+            .build();
+        let mut writer = SerializedFileWriter::new(
+            File::create(path).unwrap(),
+            Arc::new(schema),
+            Arc::new(properties),
+        )
+        .unwrap();
+
+        let mut row_group_writer = writer.next_row_group().unwrap();
+        let mut column_writer = row_group_writer.next_column().unwrap().unwrap();
+        let values = [Int96::from(vec![1, 2, 3]), Int96::from(vec![4, 5, 6])];
+
+        column_writer
+            .typed::<Int96Type>()
+            .write_batch(&values, None, None)
+            .unwrap();
+        column_writer.close().unwrap();
+        row_group_writer.close().unwrap();
+        writer.close().unwrap();
+    }
+}
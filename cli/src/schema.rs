@@ -0,0 +1,198 @@
+use crate::Error;
+use parquet::file::metadata::KeyValue;
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::SchemaDescriptor;
+use parquetry_gen::Config;
+use parquetry_gen::schema::{GenField, GenSchema, GenType};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// The canonical message type syntax
+    #[default]
+    Text,
+    /// The tree of generated fields and types
+    Json,
+    /// The equivalent Arrow schema
+    Arrow,
+    /// The generated struct definitions
+    Rust,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Schema {
+    pub type_name: String,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Field {
+    pub name: String,
+    pub rust_type: String,
+    pub optional: bool,
+    #[serde(rename = "type")]
+    pub gen_type: FieldType,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldType {
+    Column {
+        index: usize,
+        path: String,
+        physical_type: String,
+        logical_type: Option<String>,
+    },
+    Struct {
+        fields: Vec<Field>,
+        def_depth: usize,
+        rep_depth: usize,
+    },
+    List {
+        element_optional: bool,
+        element_struct_name: String,
+        element: Box<Self>,
+        def_depth: usize,
+        rep_depth: usize,
+    },
+}
+
+/// Print the schema of a Parquet file or schema definition file.
+pub fn schema<P: AsRef<Path>, W: Write>(
+    input: P,
+    format: Format,
+    config: Config,
+    output: &mut W,
+) -> Result<(), Error> {
+    let (descriptor, key_value_metadata) = open(input)?;
+
+    match format {
+        Format::Text => {
+            // The printer ignores write errors, so we render into a buffer and write that.
+            let mut buffer = vec![];
+            parquet::schema::printer::print_schema(&mut buffer, descriptor.root_schema());
+
+            output.write_all(&buffer)?;
+        }
+        Format::Json => {
+            let gen_schema = GenSchema::from_schema(&descriptor, config)?;
+            let schema = Schema {
+                type_name: gen_schema.type_name,
+                fields: gen_schema.gen_fields.iter().map(Field::new).collect(),
+            };
+
+            serde_json::to_writer_pretty(&mut *output, &schema)?;
+            writeln!(output)?;
+        }
+        Format::Arrow => {
+            let arrow_schema =
+                parquet::arrow::parquet_to_arrow_schema(&descriptor, key_value_metadata.as_ref())?;
+
+            for field in arrow_schema.fields() {
+                writeln!(
+                    output,
+                    "{}: {}{}",
+                    field.name(),
+                    if field.is_nullable() { "" } else { "non-null " },
+                    field.data_type()
+                )?;
+            }
+        }
+        Format::Rust => {
+            let gen_schema = GenSchema::from_schema(&descriptor, config)?;
+
+            write!(output, "{}", parquetry_gen::struct_code(&gen_schema)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the schema from a Parquet file footer (along with its key-value metadata) or from a schema definition file.
+fn open<P: AsRef<Path>>(input: P) -> Result<(SchemaDescriptor, Option<Vec<KeyValue>>), Error> {
+    let mut file = File::open(input)?;
+    let mut bytes = Vec::with_capacity(PARQUET_MAGIC.len());
+
+    // A single read may return fewer bytes than requested, so we read until we have the prefix or reach the end.
+    Read::by_ref(&mut file)
+        .take(PARQUET_MAGIC.len() as u64)
+        .read_to_end(&mut bytes)?;
+
+    if bytes == PARQUET_MAGIC {
+        let reader = SerializedFileReader::new(file)?;
+        let file_metadata = reader.metadata().file_metadata();
+
+        Ok((
+            file_metadata.schema_descr().clone(),
+            file_metadata.key_value_metadata().cloned(),
+        ))
+    } else {
+        file.read_to_end(&mut bytes)?;
+
+        let source = String::from_utf8(bytes).map_err(|error| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, error.utf8_error())
+        })?;
+
+        Ok((
+            SchemaDescriptor::new(Arc::new(parse_message_type(&source)?)),
+            None,
+        ))
+    }
+}
+
+impl Field {
+    fn new(gen_field: &GenField) -> Self {
+        Self {
+            name: gen_field.name.clone(),
+            rust_type: gen_field.type_name(),
+            optional: gen_field.optional,
+            gen_type: FieldType::new(&gen_field.gen_type),
+        }
+    }
+}
+
+impl FieldType {
+    fn new(gen_type: &GenType) -> Self {
+        match gen_type {
+            GenType::Column(gen_column) => Self::Column {
+                index: gen_column.index,
+                path: gen_column.descriptor.path().string(),
+                physical_type: gen_column.descriptor.physical_type().to_string(),
+                logical_type: gen_column
+                    .descriptor
+                    .logical_type_ref()
+                    .map(|logical_type| format!("{logical_type:?}")),
+            },
+            GenType::Struct {
+                gen_fields,
+                def_depth,
+                rep_depth,
+            } => Self::Struct {
+                fields: gen_fields.iter().map(Field::new).collect(),
+                def_depth: *def_depth,
+                rep_depth: *rep_depth,
+            },
+            GenType::List {
+                element_optional,
+                element_gen_type,
+                element_struct_name,
+                def_depth,
+                rep_depth,
+            } => Self::List {
+                element_optional: *element_optional,
+                element_struct_name: element_struct_name.clone(),
+                element: Box::new(Self::new(element_gen_type)),
+                def_depth: *def_depth,
+                rep_depth: *rep_depth,
+            },
+        }
+    }
+}
@@ -0,0 +1,646 @@
+use crate::{
+    Error,
+    column::{ColumnData, RowRange, Values},
+    convert::Shredder,
+};
+use parquet::basic::Compression;
+use parquet::file::metadata::SortingColumn;
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::{SchemaDescPtr, SchemaDescriptor};
+use parquetry::sort::SortValue;
+use parquetry_gen::{Config, schema::GenSchema};
+use parquetry_sort::backend::{Backend, RocksDbBackend, SledBackend};
+use parquetry_sort::encoding::{LengthPrefixed, push_length_prefixed};
+use parquetry_sort::{MergePolicy, RecordSorter};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const BATCH_SIZE: usize = 10_000;
+
+type Entry = (Vec<u8>, Vec<u8>);
+
+/// The storage used for sorting.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum SortBackend {
+    /// A temporary rocksdb database
+    #[default]
+    #[value(name = "rocksdb")]
+    RocksDb,
+    /// A temporary sled database
+    Sled,
+    /// Sorted runs in temporary files
+    External,
+}
+
+/// Which rows are kept when several rows have the same sort key.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum Keep {
+    /// All rows, in input order
+    #[default]
+    All,
+    /// The first row in input order
+    First,
+    /// The last row in input order
+    Last,
+}
+
+impl Keep {
+    const fn merge_policy(self) -> MergePolicy<()> {
+        match self {
+            Self::All => MergePolicy::KeepAll,
+            Self::First => MergePolicy::KeepFirst,
+            Self::Last => MergePolicy::KeepLast,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SortOptions {
+    /// Column paths, where a `-` prefix indicates descending order and a `:nulls-first` suffix sorts nulls first
+    pub by: Vec<String>,
+    /// The maximum number of rows in each row group
+    pub row_group_size: usize,
+    pub compression: Compression,
+    pub backend: SortBackend,
+    pub keep: Keep,
+    /// The number of bytes to buffer before spilling a sorted run (for the external backend)
+    pub memory_budget: Option<usize>,
+    /// The directory for the temporary sort database or sorted runs
+    pub temp_dir: Option<PathBuf>,
+}
+
+/// Sort the rows of a file by the given columns, returning the number of rows written.
+///
+/// Sort keys use the same encoding as generated `sort_key_value` implementations, and rows are stored in a
+/// temporary sort database (or in sorted runs in temporary files), so the input does not need to fit in memory. Rows
+/// are stored as the encoded levels and values of each column, so every input value is written back unchanged.
+pub fn sort<I: AsRef<Path>, O: AsRef<Path>>(
+    input: I,
+    output: O,
+    options: &SortOptions,
+) -> Result<usize, Error> {
+    let reader = SerializedFileReader::new(File::open(input)?)?;
+    let file_metadata = reader.metadata().file_metadata();
+    let descriptor = file_metadata.schema_descr_ptr();
+
+    // The output is written by interpreting the schema, so it must be supported by the code generator.
+    let gen_schema = GenSchema::from_schema(&descriptor, Config::default())?;
+
+    let columns = options
+        .by
+        .iter()
+        .map(|spec| SortColumnSpec::parse(spec, &descriptor, &gen_schema))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let temp_dir = match &options.temp_dir {
+        Some(temp_dir) => tempfile::tempdir_in(temp_dir)?,
+        None => tempfile::tempdir()?,
+    };
+
+    let properties = WriterProperties::builder()
+        .set_compression(options.compression)
+        .set_sorting_columns(Some(
+            columns.iter().map(SortColumnSpec::sorting_column).collect(),
+        ))
+        .set_key_value_metadata(file_metadata.key_value_metadata().cloned())
+        .build();
+
+    let mut output = SortedWriter::new(
+        File::create(output)?,
+        &descriptor,
+        &gen_schema,
+        properties,
+        options.row_group_size,
+    )?;
+
+    let policy = options.keep.merge_policy();
+
+    match options.backend {
+        SortBackend::RocksDb => {
+            let mut db_options = rocksdb::Options::default();
+            db_options.create_if_missing(true);
+
+            let backend = RocksDbBackend::open_with_policy(temp_dir.path(), db_options, policy)?;
+
+            sort_with_backend(&backend, &reader, &descriptor, &columns, &mut output)?;
+        }
+        SortBackend::Sled => {
+            let backend = SledBackend::open_with_policy(temp_dir.path(), policy)?;
+
+            sort_with_backend(&backend, &reader, &descriptor, &columns, &mut output)?;
+        }
+        SortBackend::External => {
+            let mut sorter = RecordSorter::new().with_temp_dir(temp_dir.path());
+
+            if let Some(memory_budget) = options.memory_budget {
+                sorter = sorter.with_memory_budget(memory_budget);
+            }
+
+            read_entries(&reader, &descriptor, &columns, |batch| {
+                for (key, value) in batch {
+                    sorter.insert(key, value)?;
+                }
+
+                Ok(())
+            })?;
+
+            sorter.merge(|records| {
+                // The backends combine values with the same key as they are stored, but here we have to do it as the
+                // sorted runs are merged.
+                let mut group_key = None;
+                let mut group = vec![];
+
+                for record in records {
+                    let (key, value) = record?;
+
+                    if matches!(policy, MergePolicy::KeepAll) {
+                        output.add_values(&value)?;
+                    } else {
+                        if group_key.as_deref() != Some(key.as_ref()) {
+                            output.add_values(
+                                &policy.merge_encoded(group.iter().map(Vec::as_slice))?,
+                            )?;
+                            group.clear();
+                            group_key = Some(key.into_owned());
+                        }
+
+                        group.push(value.into_owned());
+                    }
+                }
+
+                output.add_values(&policy.merge_encoded(group.iter().map(Vec::as_slice))?)
+            })??;
+        }
+    }
+
+    output.finish()
+}
+
+/// Store the rows in the backend, and write them to the output in sort key order.
+fn sort_with_backend<B: Backend, W: std::io::Write + Send>(
+    backend: &B,
+    reader: &SerializedFileReader<File>,
+    descriptor: &SchemaDescriptor,
+    columns: &[SortColumnSpec],
+    output: &mut SortedWriter<W>,
+) -> Result<(), Error> {
+    read_entries(reader, descriptor, columns, |batch| {
+        // The database is temporary, so there's no need for the write-ahead log.
+        Ok(backend.append_batch(&batch, false)?)
+    })?;
+
+    for entry in backend.entries(None, None) {
+        let (_, value_bytes) = entry?;
+
+        output.add_values(value_bytes.as_ref())?;
+    }
+
+    Ok(())
+}
+
+/// Read the rows of the file in batches of entries, where each entry is a sort key and a length-prefixed encoded row.
+fn read_entries<F: FnMut(Vec<Entry>) -> Result<(), Error>>(
+    reader: &SerializedFileReader<File>,
+    descriptor: &SchemaDescriptor,
+    columns: &[SortColumnSpec],
+    mut f: F,
+) -> Result<(), Error> {
+    let mut buffers = descriptor
+        .columns()
+        .iter()
+        .map(|column| ColumnData::new(column.physical_type()))
+        .collect::<Vec<_>>();
+
+    for row_group_index in 0..reader.num_row_groups() {
+        let row_group = reader.get_row_group(row_group_index)?;
+        let mut column_readers = (0..descriptor.num_columns())
+            .map(|index| row_group.get_column_reader(index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        loop {
+            let mut row_count = 0;
+
+            for ((column_reader, buffer), column) in column_readers
+                .iter_mut()
+                .zip(&mut buffers)
+                .zip(descriptor.columns())
+            {
+                row_count = buffer.read_records(column_reader, column, BATCH_SIZE)?;
+            }
+
+            if row_count == 0 {
+                break;
+            }
+
+            let rows = buffers
+                .iter()
+                .zip(descriptor.columns())
+                .map(|(buffer, column)| buffer.rows(column))
+                .collect::<Vec<_>>();
+
+            let mut batch = Vec::with_capacity(row_count);
+
+            for row_index in 0..row_count {
+                let mut key = vec![];
+
+                for column in columns {
+                    column.write_sort_bytes(
+                        &buffers[column.column_index],
+                        &rows[column.column_index][row_index],
+                        &mut key,
+                    );
+                }
+
+                let mut row = vec![];
+
+                for (buffer, rows) in buffers.iter().zip(&rows) {
+                    buffer.encode_row(&rows[row_index], &mut row);
+                }
+
+                let mut bytes = vec![];
+                push_length_prefixed(&mut bytes, &row);
+                batch.push((key, bytes));
+            }
+
+            f(batch)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes sorted encoded rows to a file, starting a new row group whenever the current one is full.
+struct SortedWriter<W: std::io::Write + Send> {
+    writer: SerializedFileWriter<W>,
+    shredder: Shredder,
+    row_group_size: usize,
+}
+
+impl<W: std::io::Write + Send> SortedWriter<W> {
+    fn new(
+        writer: W,
+        descriptor: &SchemaDescPtr,
+        gen_schema: &GenSchema,
+        properties: WriterProperties,
+        row_group_size: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            writer: SerializedFileWriter::new(
+                writer,
+                descriptor.root_schema_ptr(),
+                Arc::new(properties),
+            )?,
+            shredder: Shredder::new(gen_schema.gen_columns()),
+            row_group_size,
+        })
+    }
+
+    /// Add a sequence of length-prefixed encoded rows.
+    fn add_values(&mut self, value_bytes: &[u8]) -> Result<(), Error> {
+        for record in LengthPrefixed::new(value_bytes) {
+            self.shredder.add_encoded_row(record?)?;
+
+            if self.shredder.row_group_row_count() >= self.row_group_size {
+                self.shredder.write_row_group(&mut self.writer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<usize, Error> {
+        if self.shredder.row_group_row_count() > 0 {
+            self.shredder.write_row_group(&mut self.writer)?;
+        }
+
+        self.writer.close()?;
+
+        Ok(self.shredder.row_count())
+    }
+}
+
+struct SortColumnSpec {
+    column_index: usize,
+    index: i32,
+    descending: bool,
+    nulls_first: bool,
+    optional: bool,
+    variant: &'static str,
+}
+
+impl SortColumnSpec {
+    /// Parse a column path with an optional `-` prefix (for descending order) and an optional `:nulls-first` or
+    /// `:nulls-last` suffix (nulls are sorted last by default).
+    fn parse(spec: &str, schema: &SchemaDescriptor, gen_schema: &GenSchema) -> Result<Self, Error> {
+        let (spec, nulls_first) = match spec.rsplit_once(':') {
+            Some((spec, "nulls-first")) => (spec, true),
+            Some((spec, "nulls-last")) => (spec, false),
+            _ => (spec, false),
+        };
+
+        let (name, descending) = spec
+            .strip_prefix('-')
+            .map_or((spec, false), |name| (name, true));
+
+        let (column_index, column) = schema
+            .columns()
+            .iter()
+            .enumerate()
+            .find(|(_, column)| column.path().string() == name)
+            .ok_or_else(|| Error::UnknownColumn(name.to_string()))?;
+
+        // Only non-repeated columns can be sort columns (as in generated code).
+        if column.max_rep_level() > 0 {
+            return Err(Error::UnsupportedSortColumn(name.to_string()));
+        }
+
+        let gen_column = gen_schema
+            .gen_columns()
+            .into_iter()
+            .find(|gen_column| gen_column.index == column_index)
+            .ok_or_else(|| Error::UnknownColumn(name.to_string()))?;
+
+        Ok(Self {
+            column_index,
+            index: i32::try_from(column_index)
+                .map_err(|_| Error::UnsupportedSortColumn(name.to_string()))?,
+            descending,
+            nulls_first,
+            optional: column.max_def_level() > 0,
+            variant: gen_column.mapping.row_field_variant(),
+        })
+    }
+
+    const fn sorting_column(&self) -> SortingColumn {
+        SortingColumn {
+            column_idx: self.index,
+            descending: self.descending,
+            nulls_first: self.nulls_first,
+        }
+    }
+
+    /// Write the sort key encoding of the column's value in a row (which has a single level, since the column is not
+    /// repeated).
+    fn write_sort_bytes(&self, column: &ColumnData, row: &RowRange, bytes: &mut Vec<u8>) {
+        let value = (!row.values.is_empty()).then_some(ColumnSortValue {
+            values: &column.values,
+            index: row.values.start,
+            variant: self.variant,
+        });
+
+        if self.optional {
+            value.write_sort_bytes(self.descending, self.nulls_first, bytes);
+        } else if let Some(value) = value {
+            value.write_sort_bytes(self.descending, self.nulls_first, bytes);
+        }
+    }
+}
+
+/// A column value with the sort key encoding of the corresponding generated field type.
+struct ColumnSortValue<'a> {
+    values: &'a Values,
+    index: usize,
+    variant: &'static str,
+}
+
+impl SortValue for ColumnSortValue<'_> {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>) {
+        let index = self.index;
+
+        match (self.values, self.variant) {
+            (Values::Boolean(values), _) => {
+                values[index].write_sort_bytes(descending, nulls_first, bytes);
+            }
+            (Values::Int32(values), "UInt") => u32::from_ne_bytes(values[index].to_ne_bytes())
+                .write_sort_bytes(descending, nulls_first, bytes),
+            // Dates are encoded as days since the epoch.
+            (Values::Int32(values), _) => {
+                values[index].write_sort_bytes(descending, nulls_first, bytes);
+            }
+            (Values::Int64(values), "ULong") => u64::from_ne_bytes(values[index].to_ne_bytes())
+                .write_sort_bytes(descending, nulls_first, bytes),
+            // Timestamps are always encoded as microseconds.
+            (Values::Int64(values), "TimestampMillis") => values[index]
+                .saturating_mul(1000)
+                .write_sort_bytes(descending, nulls_first, bytes),
+            (Values::Int64(values), _) => {
+                values[index].write_sort_bytes(descending, nulls_first, bytes);
+            }
+            (Values::Float(values), _) => {
+                values[index].write_sort_bytes(descending, nulls_first, bytes);
+            }
+            (Values::Double(values), _) => {
+                values[index].write_sort_bytes(descending, nulls_first, bytes);
+            }
+            (Values::ByteArray(values), _) => {
+                values[index]
+                    .data()
+                    .write_sort_bytes(descending, nulls_first, bytes);
+            }
+            // Fixed-length byte arrays are written without escaping.
+            (Values::FixedLenByteArray(values), _) => {
+                parquetry::sort::write_bytes(values[index].data(), descending, bytes);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Keep, SortBackend, SortColumnSpec, SortOptions};
+    use crate::test_data;
+    use parquet::basic::Compression;
+    use parquet::file::reader::FileReader;
+    use parquet::file::serialized_reader::SerializedFileReader;
+    use parquetry::{Schema, sort::Sort};
+    use parquetry_gen::{Config, schema::GenSchema};
+    use parquetry_test::simple::{Simple, columns::SortColumn};
+    use std::fs::File;
+
+    /// Column specs and the equivalent generated sort columns.
+    fn sort_columns() -> Vec<(Vec<&'static str>, Vec<Sort<SortColumn>>)> {
+        vec![
+            (vec!["abc"], vec![Sort::new(SortColumn::Abc)]),
+            (
+                vec!["def", "-vwx:nulls-first", "abc"],
+                vec![
+                    Sort::new(SortColumn::Def),
+                    Sort::new(SortColumn::Vwx).descending().nulls_first(),
+                    Sort::new(SortColumn::Abc),
+                ],
+            ),
+            (
+                vec!["-def:nulls-last", "req_def"],
+                vec![
+                    Sort::new(SortColumn::Def).descending(),
+                    Sort::new(SortColumn::ReqDef),
+                ],
+            ),
+            (
+                vec!["mno", "-pqr:nulls-first", "stu"],
+                vec![
+                    Sort::new(SortColumn::Mno),
+                    Sort::new(SortColumn::Pqr).descending().nulls_first(),
+                    Sort::new(SortColumn::Stu),
+                ],
+            ),
+            (
+                vec!["-yza", "vwx"],
+                vec![
+                    Sort::new(SortColumn::Yza).descending(),
+                    Sort::new(SortColumn::Vwx),
+                ],
+            ),
+            (
+                vec!["-abcd", "efgh:nulls-first", "-ijkl"],
+                vec![
+                    Sort::new(SortColumn::Abcd).descending(),
+                    Sort::new(SortColumn::Efgh).nulls_first(),
+                    Sort::new(SortColumn::Ijkl).descending(),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn sort_key_encoding() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("input.parquet");
+        let values = test_data::simple_values();
+
+        test_data::write_simple(
+            &input,
+            vec![values[..4].to_vec(), values[4..].to_vec()],
+            Compression::UNCOMPRESSED,
+        );
+
+        let reader = SerializedFileReader::new(File::open(&input).unwrap()).unwrap();
+        let descriptor = reader.metadata().file_metadata().schema_descr_ptr();
+        let gen_schema = GenSchema::from_schema(&descriptor, Config::default()).unwrap();
+
+        for (specs, sorts) in sort_columns() {
+            let columns = specs
+                .iter()
+                .map(|spec| SortColumnSpec::parse(spec, &descriptor, &gen_schema).unwrap())
+                .collect::<Vec<_>>();
+            let sort_key = Simple::sort_key(&sorts).unwrap();
+
+            let mut keys = vec![];
+
+            super::read_entries(&reader, &descriptor, &columns, |batch| {
+                keys.extend(batch.into_iter().map(|(key, _)| key));
+                Ok(())
+            })
+            .unwrap();
+
+            let expected = values
+                .iter()
+                .map(|value| value.sort_key_value(&sort_key))
+                .collect::<Vec<_>>();
+
+            assert_eq!(keys, expected, "{specs:?}");
+        }
+    }
+
+    #[test]
+    fn sort_unsupported_column() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("input.parquet");
+
+        test_data::write_simple(
+            &input,
+            vec![test_data::simple_values()],
+            Compression::UNCOMPRESSED,
+        );
+
+        let reader = SerializedFileReader::new(File::open(&input).unwrap()).unwrap();
+        let descriptor = reader.metadata().file_metadata().schema_descr_ptr();
+        let gen_schema = GenSchema::from_schema(&descriptor, Config::default()).unwrap();
+
+        assert!(matches!(
+            SortColumnSpec::parse("ghi.list.element", &descriptor, &gen_schema),
+            Err(crate::Error::UnsupportedSortColumn(_))
+        ));
+        assert!(matches!(
+            SortColumnSpec::parse("xyz", &descriptor, &gen_schema),
+            Err(crate::Error::UnknownColumn(_))
+        ));
+    }
+
+    fn check_sort(backend: SortBackend, memory_budget: Option<usize>) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("input.parquet");
+        let output = temp_dir.path().join("output.parquet");
+        let values = test_data::simple_values();
+
+        test_data::write_simple(
+            &input,
+            vec![values[..7].to_vec(), values[7..].to_vec()],
+            Compression::SNAPPY,
+        );
+
+        for keep in [Keep::All, Keep::First, Keep::Last] {
+            for (specs, sorts) in sort_columns() {
+                let options = SortOptions {
+                    by: specs.iter().map(|spec| (*spec).to_string()).collect(),
+                    row_group_size: 3,
+                    compression: Compression::UNCOMPRESSED,
+                    backend,
+                    keep,
+                    memory_budget,
+                    temp_dir: Some(temp_dir.path().to_path_buf()),
+                };
+
+                let row_count = super::sort(&input, &output, &options).unwrap();
+
+                let sort_key = Simple::sort_key(&sorts).unwrap();
+                let mut expected = values.clone();
+                expected.sort_by_key(|value| value.sort_key_value(&sort_key));
+
+                match keep {
+                    Keep::All => {}
+                    Keep::First => expected.dedup_by_key(|value| value.sort_key_value(&sort_key)),
+                    Keep::Last => {
+                        expected.reverse();
+                        expected.dedup_by_key(|value| value.sort_key_value(&sort_key));
+                        expected.reverse();
+                    }
+                }
+
+                let sorted = test_data::read_simple(&output);
+
+                assert_eq!(row_count, expected.len());
+                test_data::assert_same(&sorted, &expected);
+
+                let reader = SerializedFileReader::new(File::open(&output).unwrap()).unwrap();
+                let sorting_columns = reader.metadata().row_group(0).sorting_columns().unwrap();
+
+                assert_eq!(sorting_columns.len(), specs.len());
+            }
+        }
+    }
+
+    #[test]
+    fn sort_rocksdb() {
+        check_sort(SortBackend::RocksDb, None);
+    }
+
+    #[test]
+    fn sort_sled() {
+        check_sort(SortBackend::Sled, None);
+    }
+
+    #[test]
+    fn sort_external() {
+        check_sort(SortBackend::External, None);
+    }
+
+    #[test]
+    fn sort_external_spilled() {
+        check_sort(SortBackend::External, Some(64));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    Error,
+    merge::{Input, Output},
+};
+use parquet::basic::Compression;
+use parquet::file::metadata::KeyValue;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug)]
+pub struct SplitOptions {
+    /// The maximum number of row groups in each output file
+    pub max_row_groups: Option<usize>,
+    /// The maximum number of rows in each output file
+    pub max_rows: Option<usize>,
+    /// The output compression codec (the input's codec is used if this is not provided)
+    pub compression: Option<Compression>,
+}
+
+/// Split a file into pieces with bounded numbers of row groups or rows, returning the paths of the new files.
+///
+/// Output files are named after the input file with a numeric suffix. Row groups are copied without re-encoding
+/// when possible, and are only re-encoded (one row group at a time) if their compression changes or if they have more
+/// rows than an output file may contain. Key-value metadata is preserved.
+pub fn split<I: AsRef<Path>, O: AsRef<Path>>(
+    input: I,
+    output_dir: O,
+    options: &SplitOptions,
+) -> Result<Vec<PathBuf>, Error> {
+    let input = Input::open(input)?;
+    let stem = input
+        .path
+        .file_stem()
+        .map_or_else(|| "part".into(), |stem| stem.to_string_lossy());
+
+    let mut splitter = Splitter {
+        output_dir: output_dir.as_ref(),
+        stem: &stem,
+        input: &input,
+        compression: options
+            .compression
+            .unwrap_or_else(|| input.default_compression()),
+        key_value_metadata: input
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .cloned(),
+        paths: vec![],
+        current: None,
+    };
+
+    for index in 0..input.metadata().num_row_groups() {
+        let row_count =
+            usize::try_from(input.metadata().row_group(index).num_rows()).unwrap_or_default();
+
+        let has_row_group_space = |output: &Output| {
+            options
+                .max_row_groups
+                .is_none_or(|max_row_groups| output.row_group_count() < max_row_groups)
+        };
+
+        if options
+            .max_rows
+            .is_none_or(|max_rows| row_count <= max_rows)
+        {
+            // The row group fits in a single file, so it can be appended as a whole.
+            let output = match splitter.current.take() {
+                Some(output)
+                    if has_row_group_space(&output)
+                        && options
+                            .max_rows
+                            .is_none_or(|max_rows| output.row_count() + row_count <= max_rows) =>
+                {
+                    splitter.current.insert(output)
+                }
+                Some(output) => {
+                    output.close()?;
+                    splitter.next_output()?
+                }
+                None => splitter.next_output()?,
+            };
+
+            output.append_row_group(&input, index)?;
+        } else {
+            // The row group is split into row groups that fill the current file and then whole files.
+            let max_rows = options.max_rows.unwrap_or(usize::MAX);
+            let mut columns = input.row_group_columns(index)?;
+            let mut remaining = row_count;
+
+            while remaining > 0 {
+                let output = match splitter.current.take() {
+                    Some(output)
+                        if has_row_group_space(&output) && output.row_count() < max_rows =>
+                    {
+                        splitter.current.insert(output)
+                    }
+                    Some(output) => {
+                        output.close()?;
+                        splitter.next_output()?
+                    }
+                    None => splitter.next_output()?,
+                };
+
+                let rows_written = output
+                    .append_rows(&mut columns, remaining.min(max_rows - output.row_count()))?;
+
+                if rows_written == 0 {
+                    break;
+                }
+
+                remaining -= rows_written;
+            }
+        }
+    }
+
+    if let Some(output) = splitter.current.take() {
+        output.close()?;
+    }
+
+    Ok(splitter.paths)
+}
+
+struct Splitter<'a> {
+    output_dir: &'a Path,
+    stem: &'a str,
+    input: &'a Input,
+    compression: Compression,
+    key_value_metadata: Option<Vec<KeyValue>>,
+    paths: Vec<PathBuf>,
+    current: Option<Output>,
+}
+
+impl Splitter<'_> {
+    /// Create a new output file (the current output must already have been closed).
+    fn next_output(&mut self) -> Result<&mut Output, Error> {
+        let path = self
+            .output_dir
+            .join(format!("{}-{:05}.parquet", self.stem, self.paths.len()));
+
+        let output = Output::create(
+            &path,
+            self.input.descriptor(),
+            self.compression,
+            self.key_value_metadata.clone(),
+        )?;
+
+        self.paths.push(path);
+
+        Ok(self.current.insert(output))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SplitOptions;
+    use crate::test_data;
+    use parquet::basic::Compression;
+    use parquet::file::metadata::RowGroupMetaData;
+    use parquet::file::reader::FileReader;
+    use parquet::file::serialized_reader::SerializedFileReader;
+    use std::fs::File;
+
+    /// Split a file with row groups of 5, 12, and 3 rows, and return the row group sizes of each output file.
+    fn split(options: &SplitOptions) -> Vec<Vec<i64>> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("input.parquet");
+        let output_dir = temp_dir.path().join("output");
+        let values = (0..20)
+            .map(|abc| test_data::simple(abc, Some("a"), Some(f64::from(abc))))
+            .collect::<Vec<_>>();
+
+        test_data::write_simple(
+            &input,
+            vec![
+                values[..5].to_vec(),
+                values[5..17].to_vec(),
+                values[17..].to_vec(),
+            ],
+            Compression::SNAPPY,
+        );
+
+        std::fs::create_dir(&output_dir).unwrap();
+
+        let paths = super::split(&input, &output_dir, options).unwrap();
+
+        let mut split_values = vec![];
+        let mut row_group_sizes = vec![];
+
+        for (index, path) in paths.iter().enumerate() {
+            assert_eq!(path, &output_dir.join(format!("input-{index:05}.parquet")));
+
+            let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+            let compression = options.compression.unwrap_or(Compression::SNAPPY);
+
+            assert!(reader.metadata().row_groups().iter().all(|row_group| {
+                row_group
+                    .columns()
+                    .iter()
+                    .all(|column| column.compression() == compression)
+            }));
+
+            row_group_sizes.push(
+                reader
+                    .metadata()
+                    .row_groups()
+                    .iter()
+                    .map(RowGroupMetaData::num_rows)
+                    .collect(),
+            );
+            split_values.extend(test_data::read_simple(path));
+        }
+
+        test_data::assert_same(&split_values, &values);
+
+        row_group_sizes
+    }
+
+    #[test]
+    fn split_max_row_groups() {
+        let row_group_sizes = split(&SplitOptions {
+            max_row_groups: Some(1),
+            max_rows: None,
+            compression: None,
+        });
+
+        assert_eq!(row_group_sizes, vec![vec![5], vec![12], vec![3]]);
+
+        let row_group_sizes = split(&SplitOptions {
+            max_row_groups: Some(2),
+            max_rows: None,
+            compression: Some(Compression::UNCOMPRESSED),
+        });
+
+        assert_eq!(row_group_sizes, vec![vec![5, 12], vec![3]]);
+    }
+
+    #[test]
+    fn split_max_rows() {
+        let row_group_sizes = split(&SplitOptions {
+            max_row_groups: None,
+            max_rows: Some(4),
+            compression: None,
+        });
+
+        assert_eq!(
+            row_group_sizes,
+            vec![vec![4], vec![1, 3], vec![4], vec![4], vec![1, 3]]
+        );
+
+        let row_group_sizes = split(&SplitOptions {
+            max_row_groups: None,
+            max_rows: Some(17),
+            compression: Some(Compression::UNCOMPRESSED),
+        });
+
+        assert_eq!(row_group_sizes, vec![vec![5, 12], vec![3]]);
+    }
+
+    #[test]
+    fn split_max_row_groups_and_rows() {
+        let row_group_sizes = split(&SplitOptions {
+            max_row_groups: Some(2),
+            max_rows: Some(10),
+            compression: None,
+        });
+
+        assert_eq!(row_group_sizes, vec![vec![5, 5], vec![7, 3]]);
+
+        let row_group_sizes = split(&SplitOptions {
+            max_row_groups: Some(1),
+            max_rows: Some(10),
+            compression: None,
+        });
+
+        assert_eq!(row_group_sizes, vec![vec![5], vec![10], vec![2], vec![3]]);
+    }
+}
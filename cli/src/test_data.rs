@@ -0,0 +1,67 @@
+//! Test files written with the test crate's generated code.
+
+use chrono::DateTime;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet::file::serialized_reader::ReadOptionsBuilder;
+use parquetry::Schema;
+use parquetry_test::simple::Simple;
+use std::fs::File;
+use std::path::Path;
+
+/// A value where every field other than `abc`, `def`, and `vwx` is derived from `abc`.
+pub fn simple(abc: u8, def: Option<&str>, vwx: Option<f64>) -> Simple {
+    Simple::new(
+        u64::from(abc),
+        def.map(str::to_string),
+        format!("req-{abc}"),
+        vec![Some(abc.is_multiple_of(2)), None],
+        abc.is_multiple_of(3).then(|| vec![true, false]),
+        abc.is_multiple_of(4),
+        (!abc.is_multiple_of(5))
+            .then(|| DateTime::from_timestamp_millis(i64::from(abc) * -1_234_567))
+            .flatten(),
+        DateTime::from_timestamp_micros(i64::from(abc) * 987_654_321).unwrap(),
+        vwx,
+        f32::from(abc) * -0.5,
+        [abc; 20],
+        (abc % 2 == 1).then(|| vec![abc; usize::from(abc % 4)]),
+        (abc % 3 != 1).then_some([abc, 0, abc]),
+    )
+    .unwrap()
+}
+
+/// Values with negative, null, infinite, and `NaN` floats, and duplicate `def` and `vwx` pairs.
+pub fn simple_values() -> Vec<Simple> {
+    vec![
+        simple(0, Some("b"), Some(1.5)),
+        simple(1, None, Some(-2.0)),
+        simple(2, Some("a"), None),
+        simple(3, Some("b"), Some(f64::NAN)),
+        simple(4, Some(""), Some(f64::NEG_INFINITY)),
+        simple(5, Some("b"), Some(1.5)),
+        simple(6, None, None),
+        simple(7, Some("a"), Some(f64::INFINITY)),
+        simple(8, Some("b"), Some(-0.0)),
+        simple(9, Some("a"), None),
+    ]
+}
+
+pub fn write_simple<P: AsRef<Path>>(path: P, groups: Vec<Vec<Simple>>, compression: Compression) {
+    let properties = WriterProperties::builder()
+        .set_compression(compression)
+        .build();
+
+    Simple::write_row_groups(File::create(path).unwrap(), properties, groups).unwrap();
+}
+
+pub fn read_simple<P: AsRef<Path>>(path: P) -> Vec<Simple> {
+    Simple::read_path(path, ReadOptionsBuilder::new().build())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Compare values by their debug representations, so that `NaN` values are equal.
+pub fn assert_same(left: &[Simple], right: &[Simple]) {
+    assert_eq!(format!("{left:?}"), format!("{right:?}"));
+}
@@ -0,0 +1,343 @@
+use crate::Error;
+use chrono::TimeZone;
+use parquet::basic::Type as PhysicalType;
+use parquet::file::reader::FileReader;
+use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::record::{Field, Row};
+use parquet::schema::types::Type;
+use parquetry_gen::ParsedFileSchema;
+use parquetry_gen::schema::{GenField, GenType};
+use std::fmt::Display;
+use std::fs::File;
+use std::path::Path;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+    pub row_index: Option<usize>,
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(row_index) = self.row_index {
+            write!(f, "row {row_index}: ")?;
+        }
+
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Compare the file's footer schema with the schema definition, and optionally check that every row can be read
+/// by the generated code.
+pub fn validate<P: AsRef<Path>>(
+    schema: &ParsedFileSchema,
+    input: P,
+    check_rows: bool,
+) -> Result<Vec<Violation>, Error> {
+    let reader = SerializedFileReader::new(File::open(input)?)?;
+    let mut violations = compare_schemas(
+        schema.descriptor.root_schema(),
+        reader.metadata().file_metadata().schema(),
+    );
+
+    // The rows can't be interpreted meaningfully if the schemas don't match.
+    if check_rows && violations.is_empty() {
+        for (row_index, row) in reader.get_row_iter(None)?.enumerate() {
+            let row = row?;
+            let mut checker = RowChecker {
+                row_index,
+                violations: &mut violations,
+            };
+
+            checker.check_row(&schema.schema.gen_fields, &row, &[]);
+        }
+    }
+
+    Ok(violations)
+}
+
+/// Compare the fields of two root schema types (the names of the root types are ignored).
+pub fn compare_schemas(expected: &Type, found: &Type) -> Vec<Violation> {
+    let mut violations = vec![];
+    compare_fields(
+        expected.get_fields(),
+        found.get_fields(),
+        &[],
+        &mut violations,
+    );
+    violations
+}
+
+fn compare_fields(
+    expected: &[std::sync::Arc<Type>],
+    found: &[std::sync::Arc<Type>],
+    prefix: &[&str],
+    violations: &mut Vec<Violation>,
+) {
+    for (index, expected_field) in expected.iter().enumerate() {
+        let mut path = prefix.to_vec();
+        path.push(expected_field.name());
+
+        match found.get(index) {
+            Some(found_field) if found_field.name() == expected_field.name() => {
+                compare_types(expected_field, found_field, &path, violations);
+            }
+            Some(found_field) => violations.push(schema_violation(
+                &path,
+                format!(
+                    "expected field {}, found {}",
+                    expected_field.name(),
+                    found_field.name()
+                ),
+            )),
+            None => violations.push(schema_violation(&path, "missing field")),
+        }
+    }
+
+    for found_field in found.iter().skip(expected.len()) {
+        let mut path = prefix.to_vec();
+        path.push(found_field.name());
+
+        violations.push(schema_violation(&path, "unexpected field"));
+    }
+}
+
+fn compare_types(expected: &Type, found: &Type, path: &[&str], violations: &mut Vec<Violation>) {
+    let expected_info = expected.get_basic_info();
+    let found_info = found.get_basic_info();
+
+    if expected_info.repetition() != found_info.repetition() {
+        violations.push(schema_violation(
+            path,
+            format!(
+                "expected repetition {}, found {}",
+                expected_info.repetition(),
+                found_info.repetition()
+            ),
+        ));
+    }
+
+    if expected_info.logical_type_ref() != found_info.logical_type_ref() {
+        violations.push(schema_violation(
+            path,
+            format!(
+                "expected logical type {:?}, found {:?}",
+                expected_info.logical_type_ref(),
+                found_info.logical_type_ref()
+            ),
+        ));
+    } else if expected_info.logical_type_ref().is_none()
+        && expected_info.converted_type() != found_info.converted_type()
+    {
+        violations.push(schema_violation(
+            path,
+            format!(
+                "expected converted type {}, found {}",
+                expected_info.converted_type(),
+                found_info.converted_type()
+            ),
+        ));
+    }
+
+    match (expected, found) {
+        (
+            Type::PrimitiveType {
+                physical_type: expected_physical_type,
+                type_length: expected_type_length,
+                ..
+            },
+            Type::PrimitiveType {
+                physical_type: found_physical_type,
+                type_length: found_type_length,
+                ..
+            },
+        ) => {
+            if expected_physical_type != found_physical_type {
+                violations.push(schema_violation(
+                    path,
+                    format!(
+                        "expected physical type {expected_physical_type}, found {found_physical_type}"
+                    ),
+                ));
+            } else if *expected_physical_type == PhysicalType::FIXED_LEN_BYTE_ARRAY
+                && expected_type_length != found_type_length
+            {
+                violations.push(schema_violation(
+                    path,
+                    format!("expected length {expected_type_length}, found {found_type_length}"),
+                ));
+            }
+        }
+        (Type::GroupType { .. }, Type::GroupType { .. }) => {
+            compare_fields(expected.get_fields(), found.get_fields(), path, violations);
+        }
+        (Type::GroupType { .. }, Type::PrimitiveType { .. }) => {
+            violations.push(schema_violation(path, "expected group, found primitive"));
+        }
+        (Type::PrimitiveType { .. }, Type::GroupType { .. }) => {
+            violations.push(schema_violation(path, "expected primitive, found group"));
+        }
+    }
+}
+
+fn schema_violation<M: Into<String>>(path: &[&str], message: M) -> Violation {
+    Violation {
+        row_index: None,
+        path: path.join("."),
+        message: message.into(),
+    }
+}
+
+struct RowChecker<'a> {
+    row_index: usize,
+    violations: &'a mut Vec<Violation>,
+}
+
+impl RowChecker<'_> {
+    fn check_row(&mut self, gen_fields: &[GenField], row: &Row, prefix: &[String]) {
+        for gen_field in gen_fields {
+            let mut path = prefix.to_vec();
+            path.push(gen_field.name.clone());
+
+            match row
+                .get_column_iter()
+                .find(|(name, _)| **name == gen_field.name)
+            {
+                Some((_, Field::Null)) => {
+                    if !gen_field.optional {
+                        self.report(&path, "null value for required field");
+                    }
+                }
+                Some((_, field)) => self.check_value(&gen_field.gen_type, field, &path),
+                None => self.report(&path, "missing field"),
+            }
+        }
+    }
+
+    /// Check that the value converts to the field type in the same way as in the generated code.
+    fn check_value(&mut self, gen_type: &GenType, field: &Field, path: &[String]) {
+        match (gen_type, field) {
+            (GenType::Column(gen_column), field) => {
+                let expected_variant = gen_column.mapping.row_field_variant();
+
+                match field {
+                    _ if field_variant(field) != expected_variant => self.report(
+                        path,
+                        format!(
+                            "expected {expected_variant} value, found {}",
+                            field_variant(field)
+                        ),
+                    ),
+                    Field::Date(days) => {
+                        if chrono::TimeDelta::try_days(i64::from(*days))
+                            .and_then(|delta| {
+                                chrono::NaiveDate::from_ymd_opt(1970, 1, 1)
+                                    .and_then(|epoch| epoch.checked_add_signed(delta))
+                            })
+                            .is_none()
+                        {
+                            self.report(path, format!("invalid date {days}"));
+                        }
+                    }
+                    Field::TimestampMillis(value) => {
+                        if chrono::Utc.timestamp_millis_opt(*value).single().is_none() {
+                            self.report(path, format!("invalid timestamp {value}"));
+                        }
+                    }
+                    Field::TimestampMicros(value) => {
+                        if chrono::Utc.timestamp_micros(*value).single().is_none() {
+                            self.report(path, format!("invalid timestamp {value}"));
+                        }
+                    }
+                    Field::Bytes(value)
+                        if gen_column.descriptor.physical_type()
+                            == PhysicalType::FIXED_LEN_BYTE_ARRAY =>
+                    {
+                        let expected_len = gen_column.descriptor.type_length();
+
+                        if usize::try_from(expected_len).ok() != Some(value.len()) {
+                            self.report(
+                                path,
+                                format!("expected {expected_len} bytes, found {}", value.len()),
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            (GenType::Struct { gen_fields, .. }, Field::Group(row)) => {
+                self.check_row(gen_fields, row, path);
+            }
+            (
+                GenType::List {
+                    element_optional,
+                    element_gen_type,
+                    ..
+                },
+                Field::ListInternal(list),
+            ) => {
+                for (index, element) in list.elements().iter().enumerate() {
+                    let mut path = path.to_vec();
+                    path.push(index.to_string());
+
+                    match element {
+                        Field::Null => {
+                            if !element_optional {
+                                self.report(&path, "null value for required element");
+                            }
+                        }
+                        element => self.check_value(element_gen_type, element, &path),
+                    }
+                }
+            }
+            (GenType::Struct { .. }, field) => {
+                self.report(
+                    path,
+                    format!("expected group, found {}", field_variant(field)),
+                );
+            }
+            (GenType::List { .. }, field) => {
+                self.report(
+                    path,
+                    format!("expected list, found {}", field_variant(field)),
+                );
+            }
+        }
+    }
+
+    fn report<M: Into<String>>(&mut self, path: &[String], message: M) {
+        self.violations.push(Violation {
+            row_index: Some(self.row_index),
+            path: path.join("."),
+            message: message.into(),
+        });
+    }
+}
+
+const fn field_variant(field: &Field) -> &'static str {
+    match field {
+        Field::Null => "Null",
+        Field::Bool(_) => "Bool",
+        Field::Byte(_) => "Byte",
+        Field::Short(_) => "Short",
+        Field::Int(_) => "Int",
+        Field::Long(_) => "Long",
+        Field::UByte(_) => "UByte",
+        Field::UShort(_) => "UShort",
+        Field::UInt(_) => "UInt",
+        Field::ULong(_) => "ULong",
+        Field::Float(_) => "Float",
+        Field::Double(_) => "Double",
+        Field::Decimal(_) => "Decimal",
+        Field::Str(_) => "Str",
+        Field::Bytes(_) => "Bytes",
+        Field::Date(_) => "Date",
+        Field::TimestampMillis(_) => "TimestampMillis",
+        Field::TimestampMicros(_) => "TimestampMicros",
+        Field::Group(_) => "Group",
+        Field::ListInternal(_) => "List",
+        Field::MapInternal(_) => "Map",
+        _ => "unsupported value",
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, SecondsFormat};
+use parquet::basic::{ConvertedType, Repetition};
+use parquet::record::{Field, Row};
+use parquet::schema::types::Type;
+use serde_json::{Map, Value};
+
+pub fn row_to_json(row: &Row) -> Value {
+    Value::Object(
+        row.get_column_iter()
+            .map(|(name, field)| (name.clone(), field_to_json(field)))
+            .collect(),
+    )
+}
+
+/// Timestamps are represented as RFC 3339 strings and byte arrays as arrays of numbers.
+pub fn field_to_json(field: &Field) -> Value {
+    match field {
+        Field::Null => Value::Null,
+        Field::Bool(value) => Value::Bool(*value),
+        Field::Byte(value) => (*value).into(),
+        Field::Short(value) => (*value).into(),
+        Field::Int(value) => (*value).into(),
+        Field::Long(value) => (*value).into(),
+        Field::UByte(value) => (*value).into(),
+        Field::UShort(value) => (*value).into(),
+        Field::UInt(value) => (*value).into(),
+        Field::ULong(value) => (*value).into(),
+        Field::Float(value) => float_to_json(f64::from(*value)),
+        Field::Double(value) => float_to_json(*value),
+        Field::Str(value) => Value::String(value.clone()),
+        Field::Bytes(value) => {
+            Value::Array(value.data().iter().map(|byte| (*byte).into()).collect())
+        }
+        Field::TimestampMillis(value) => DateTime::from_timestamp_millis(*value)
+            .map_or_else(|| field.to_string().into(), timestamp_to_json),
+        Field::TimestampMicros(value) => DateTime::from_timestamp_micros(*value)
+            .map_or_else(|| field.to_string().into(), timestamp_to_json),
+        Field::Group(row) => row_to_json(row),
+        Field::ListInternal(list) => {
+            Value::Array(list.elements().iter().map(field_to_json).collect())
+        }
+        Field::MapInternal(map) => Value::Array(
+            map.entries()
+                .iter()
+                .map(|(key, value)| {
+                    let mut entry = Map::new();
+                    entry.insert("key".to_string(), field_to_json(key));
+                    entry.insert("value".to_string(), field_to_json(value));
+                    Value::Object(entry)
+                })
+                .collect(),
+        ),
+        other => Value::String(other.to_string()),
+    }
+}
+
+/// Render a field as a single table or CSV cell (nested values are rendered as JSON).
+pub fn field_to_cell(field: &Field) -> String {
+    match field_to_json(field) {
+        Value::Null => String::new(),
+        Value::String(value) => value,
+        other => other.to_string(),
+    }
+}
+
+/// The paths of the columns in a flattened representation of the schema, where only lists and maps are not
+/// flattened.
+pub fn flat_columns(schema: &Type) -> Vec<Vec<String>> {
+    let mut columns = vec![];
+    add_flat_columns(schema.get_fields(), &[], &mut columns);
+    columns
+}
+
+/// Find a value by its path in a flattened representation of the row.
+pub fn lookup<'a>(row: &'a Row, path: &[String]) -> Option<&'a Field> {
+    let (first, rest) = path.split_first()?;
+    let (_, field) = row.get_column_iter().find(|(name, _)| *name == first)?;
+
+    if rest.is_empty() {
+        Some(field)
+    } else {
+        match field {
+            Field::Group(row) => lookup(row, rest),
+            _ => None,
+        }
+    }
+}
+
+pub fn is_list_or_map(field_type: &Type) -> bool {
+    let info = field_type.get_basic_info();
+
+    matches!(
+        info.converted_type(),
+        ConvertedType::LIST | ConvertedType::MAP | ConvertedType::MAP_KEY_VALUE
+    ) || (info.has_repetition() && info.repetition() == Repetition::REPEATED)
+}
+
+fn add_flat_columns(
+    fields: &[std::sync::Arc<Type>],
+    prefix: &[String],
+    columns: &mut Vec<Vec<String>>,
+) {
+    for field in fields {
+        let mut path = prefix.to_vec();
+        path.push(field.name().to_string());
+
+        if field.is_group() && !is_list_or_map(field) {
+            add_flat_columns(field.get_fields(), &path, columns);
+        } else {
+            columns.push(path);
+        }
+    }
+}
+
+fn float_to_json(value: f64) -> Value {
+    serde_json::Number::from_f64(value)
+        .map_or_else(|| Value::String(value.to_string()), Value::Number)
+}
+
+fn timestamp_to_json(timestamp: DateTime<chrono::Utc>) -> Value {
+    Value::String(timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
@@ -228,12 +228,12 @@ impl parquetry::Schema for User {
     type Writer<W: std::io::Write + Send> = UserWriter<W>;
     fn sort_key_value(
         &self,
-        sort_key: parquetry::sort::SortKey<Self::SortColumn>,
+        sort_key: &parquetry::sort::SortKey<Self::SortColumn>,
     ) -> Vec<u8> {
         {
             let mut bytes = vec![];
             for column in sort_key.columns() {
-                self.write_sort_key_bytes(column, &mut bytes);
+                self.write_sort_key_bytes(*column, &mut bytes);
             }
             bytes
         }
@@ -657,24 +657,33 @@ impl User {
         match column.column {
             columns::SortColumn::Id => {
                 let value = self.id;
-                for b in value.to_be_bytes() {
-                    bytes.push(if column.descending { !b } else { b });
-                }
+                parquetry::sort::SortValue::write_sort_bytes(
+                    &value,
+                    column.descending,
+                    column.nulls_first,
+                    bytes,
+                );
             }
             columns::SortColumn::Ts => {
                 let value = self.ts;
-                for b in value.timestamp_micros().to_be_bytes() {
-                    bytes.push(if column.descending { !b } else { b });
-                }
+                parquetry::sort::SortValue::write_sort_bytes(
+                    &value,
+                    column.descending,
+                    column.nulls_first,
+                    bytes,
+                );
             }
             columns::SortColumn::Status => {
                 let value = self.status;
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.to_be_bytes() {
-                            bytes.push(if column.descending { !b } else { b });
-                        }
+                        parquetry::sort::SortValue::write_sort_bytes(
+                            &value,
+                            column.descending,
+                            column.nulls_first,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -686,10 +695,11 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.as_bytes() {
-                            bytes.push(if column.descending { !b } else { *b });
-                        }
-                        bytes.push(if column.descending { u8::MAX } else { b'\0' });
+                        parquetry::sort::write_escaped_bytes(
+                            value.as_bytes(),
+                            column.descending,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -705,10 +715,11 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.as_bytes() {
-                            bytes.push(if column.descending { !b } else { *b });
-                        }
-                        bytes.push(if column.descending { u8::MAX } else { b'\0' });
+                        parquetry::sort::write_escaped_bytes(
+                            value.as_bytes(),
+                            column.descending,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -725,9 +736,12 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.timestamp_micros().to_be_bytes() {
-                            bytes.push(if column.descending { !b } else { b });
-                        }
+                        parquetry::sort::SortValue::write_sort_bytes(
+                            &value,
+                            column.descending,
+                            column.nulls_first,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -744,15 +758,12 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in (value
-                            .signed_duration_since(
-                                chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
-                            )
-                            .num_days() as i32)
-                            .to_be_bytes()
-                        {
-                            bytes.push(if column.descending { !b } else { b });
-                        }
+                        parquetry::sort::SortValue::write_sort_bytes(
+                            &value,
+                            column.descending,
+                            column.nulls_first,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -769,10 +780,11 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.as_bytes() {
-                            bytes.push(if column.descending { !b } else { *b });
-                        }
-                        bytes.push(if column.descending { u8::MAX } else { b'\0' });
+                        parquetry::sort::write_escaped_bytes(
+                            value.as_bytes(),
+                            column.descending,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -789,10 +801,11 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.as_bytes() {
-                            bytes.push(if column.descending { !b } else { *b });
-                        }
-                        bytes.push(if column.descending { u8::MAX } else { b'\0' });
+                        parquetry::sort::write_escaped_bytes(
+                            value.as_bytes(),
+                            column.descending,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -809,10 +822,11 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.as_bytes() {
-                            bytes.push(if column.descending { !b } else { *b });
-                        }
-                        bytes.push(if column.descending { u8::MAX } else { b'\0' });
+                        parquetry::sort::write_escaped_bytes(
+                            value.as_bytes(),
+                            column.descending,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -829,9 +843,12 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.to_be_bytes() {
-                            bytes.push(if column.descending { !b } else { b });
-                        }
+                        parquetry::sort::SortValue::write_sort_bytes(
+                            &value,
+                            column.descending,
+                            column.nulls_first,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -848,9 +865,12 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.to_be_bytes() {
-                            bytes.push(if column.descending { !b } else { b });
-                        }
+                        parquetry::sort::SortValue::write_sort_bytes(
+                            &value,
+                            column.descending,
+                            column.nulls_first,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -867,9 +887,12 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.to_be_bytes() {
-                            bytes.push(if column.descending { !b } else { b });
-                        }
+                        parquetry::sort::SortValue::write_sort_bytes(
+                            &value,
+                            column.descending,
+                            column.nulls_first,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -886,9 +909,12 @@ impl User {
                 match value {
                     Some(value) => {
                         bytes.push(if column.nulls_first { 1 } else { 0 });
-                        for b in value.to_be_bytes() {
-                            bytes.push(if column.descending { !b } else { b });
-                        }
+                        parquetry::sort::SortValue::write_sort_bytes(
+                            &value,
+                            column.descending,
+                            column.nulls_first,
+                            bytes,
+                        );
                     }
                     None => {
                         bytes.push(if column.nulls_first { 0 } else { 1 });
@@ -3,7 +3,7 @@ use parquet::{basic::Type as PhysicalType, schema::types::ColumnDescPtr};
 
 use super::{
     error::Error,
-    schema::{GenColumn, GenField, GenSchema, GenStruct, GenType},
+    schema::{GenColumn, GenField, GenSchema, GenStruct, GenType, SortExpression},
     types::{DateTimeUnit, TypeMapping},
 };
 
@@ -600,7 +600,7 @@ pub fn gen_sort_key_value_block() -> Block {
     let mut block = Block::new("");
     block.line("let mut bytes = vec![];");
     block.line("for column in sort_key.columns() {");
-    block.line("self.write_sort_key_bytes(column, &mut bytes);");
+    block.line("self.write_sort_key_bytes(*column, &mut bytes);");
     block.line("}");
     block.line("bytes");
     block
@@ -613,34 +613,8 @@ pub fn gen_write_sort_key_bytes_block(schema: &GenSchema) -> Result<Block, Error
         .iter()
         .filter(|gen_column| gen_column.is_sort_column())
     {
-        let mut value_path = String::new();
-        let mut any_optional = false;
-        for (index, (part, optional)) in gen_column.rust_path.iter().enumerate() {
-            let last = index == gen_column.rust_path.len() - 1;
-
-            if any_optional {
-                if *optional {
-                    if last && gen_column.mapping.is_copy() {
-                        value_path.push_str(&format!(".and_then(|value| value.{part})"));
-                    } else {
-                        value_path.push_str(&format!(".and_then(|value| value.{part}.as_ref())"));
-                    }
-                } else if last && gen_column.mapping.is_copy() {
-                    value_path.push_str(&format!(".map(|value| value.{part})"));
-                } else {
-                    value_path.push_str(&format!(".map(|value| &value.{part})"));
-                }
-            } else {
-                value_path.push_str(&format!(".{part}"));
-
-                if *optional {
-                    if !last || !gen_column.mapping.is_copy() {
-                        value_path.push_str(".as_ref()");
-                    }
-                    any_optional = true;
-                }
-            }
-        }
+        let (value_path, any_optional) =
+            sort_value_path(&gen_column.rust_path, gen_column.mapping.is_copy());
 
         let mut code = String::new();
 
@@ -667,10 +641,87 @@ pub fn gen_write_sort_key_bytes_block(schema: &GenSchema) -> Result<Block, Error
             ));
         }
     }
+
+    for derived_sort_column in schema.derived_sort_columns() {
+        let (list_path, any_optional) = sort_value_path(&derived_sort_column.list_path, false);
+
+        let list = if any_optional {
+            format!("self{list_path}")
+        } else {
+            format!("Some(&self{list_path})")
+        };
+
+        let elements = if derived_sort_column.element_optional {
+            "list.iter().flatten()"
+        } else {
+            "list.iter()"
+        };
+
+        let element = if derived_sort_column.element_optional {
+            ".and_then(Option::as_ref)"
+        } else {
+            ""
+        };
+
+        let value = match derived_sort_column.expression {
+            SortExpression::First => format!("list.and_then(|list| list.first()){element}"),
+            SortExpression::Last => format!("list.and_then(|list| list.last()){element}"),
+            SortExpression::Min => {
+                format!("list.and_then(|list| parquetry::sort::min_by_sort_bytes({elements}))")
+            }
+            SortExpression::Max => {
+                format!("list.and_then(|list| parquetry::sort::max_by_sort_bytes({elements}))")
+            }
+            SortExpression::Length => "list.map(|list| list.len() as u64)".to_string(),
+        };
+
+        block.line(format!(
+            "columns::SortColumn::{} => {{ let list = {list}; let value = {value}; parquetry::sort::SortValue::write_sort_bytes(&value, column.descending, column.nulls_first, bytes); }},",
+            derived_sort_column.variant_name(),
+        ));
+    }
+
     Ok(block)
 }
 
-const fn physical_type_name(t: PhysicalType) -> Result<&'static str, Error> {
+/// The path to a field's value from `self`, and whether the value is optional.
+///
+/// Non-copy values are referenced if they are optional.
+fn sort_value_path(rust_path: &[(String, bool)], is_copy: bool) -> (String, bool) {
+    let mut value_path = String::new();
+    let mut any_optional = false;
+
+    for (index, (part, optional)) in rust_path.iter().enumerate() {
+        let last = index == rust_path.len() - 1;
+
+        if any_optional {
+            if *optional {
+                if last && is_copy {
+                    value_path.push_str(&format!(".and_then(|value| value.{part})"));
+                } else {
+                    value_path.push_str(&format!(".and_then(|value| value.{part}.as_ref())"));
+                }
+            } else if last && is_copy {
+                value_path.push_str(&format!(".map(|value| value.{part})"));
+            } else {
+                value_path.push_str(&format!(".map(|value| &value.{part})"));
+            }
+        } else {
+            value_path.push_str(&format!(".{part}"));
+
+            if *optional {
+                if !last || !is_copy {
+                    value_path.push_str(".as_ref()");
+                }
+                any_optional = true;
+            }
+        }
+    }
+
+    (value_path, any_optional)
+}
+
+pub const fn physical_type_name(t: PhysicalType) -> Result<&'static str, Error> {
     match t {
         PhysicalType::BOOLEAN => Ok("BoolType"),
         PhysicalType::INT32 => Ok("Int32Type"),
@@ -1,7 +1,9 @@
 use codegen::{Module, Scope};
 use convert_case::{Case, Casing};
+use std::collections::HashSet;
 
-use super::schema::GenColumn;
+use super::error::Error;
+use super::schema::{DerivedSortColumn, GenColumn};
 
 enum ColumnInfoTree {
     Leaf(GenColumn),
@@ -10,7 +12,11 @@ enum ColumnInfoTree {
 
 struct ColumnInfoBranch(Vec<(String, ColumnInfoTree)>);
 
-pub fn add_column_info_modules(scope: &mut Scope, gen_columns: &[GenColumn]) {
+pub fn add_column_info_modules(
+    scope: &mut Scope,
+    gen_columns: &[GenColumn],
+    derived_sort_columns: &[DerivedSortColumn],
+) -> Result<(), Error> {
     let module = scope.new_module("columns").vis("pub");
     let sort = module
         .new_enum("SortColumn")
@@ -45,7 +51,28 @@ pub fn add_column_info_modules(scope: &mut Scope, gen_columns: &[GenColumn]) {
         }
     }
 
-    if !sort_column_names.is_empty() {
+    let mut derived_sort_column_names = vec![];
+
+    for derived_sort_column in derived_sort_columns {
+        let name = derived_sort_column.variant_name();
+        sort.new_variant(&name);
+        derived_sort_column_names.push((name, derived_sort_column.gen_column.index));
+    }
+
+    if !derived_sort_column_names.is_empty() {
+        sort.doc("Derived columns are encoded in sort keys as optional values. Null lists have no derived value, and empty\nlists have no first, last, min, or max value (but have a length of zero). Min and max are taken according to the\nascending sort key encoding of the elements, and lengths are encoded as `u64` values.");
+    }
+
+    // Variant names are built from the last segment of the path, so columns in different structs may collide.
+    let mut seen_names = HashSet::new();
+
+    for (name, _) in sort_column_names.iter().chain(&derived_sort_column_names) {
+        if !seen_names.insert(name) {
+            return Err(Error::DuplicateSortColumn(name.clone()));
+        }
+    }
+
+    if !sort_column_names.is_empty() || !derived_sort_column_names.is_empty() {
         let sort_column_impl = module
             .new_impl("SortColumn")
             .impl_trait("parquetry::sort::SortColumn");
@@ -54,16 +81,33 @@ pub fn add_column_info_modules(scope: &mut Scope, gen_columns: &[GenColumn]) {
 
         sort_column_index.line("match self {");
 
-        for (name, index) in sort_column_names {
+        for (name, index) in sort_column_names.iter().chain(&derived_sort_column_names) {
             sort_column_index.line(format!("Self::{name} => {index},"));
         }
 
         sort_column_index.line("}");
+
+        if !derived_sort_column_names.is_empty() {
+            let sort_column_is_derived = sort_column_impl
+                .new_fn("is_derived")
+                .arg_ref_self()
+                .ret("bool");
+
+            let derived_patterns = derived_sort_column_names
+                .iter()
+                .map(|(name, _)| format!("Self::{name}"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+
+            sort_column_is_derived.line(format!("matches!(self, {derived_patterns})"));
+        }
     }
 
     for (name, tree) in root.0 {
         tree.add(module, &name);
     }
+
+    Ok(())
 }
 
 impl ColumnInfoTree {
@@ -26,5 +26,7 @@ pub enum Error {
     #[error("Unsupported repetition shape")]
     UnsupportedRepetition(String),
     #[error("Unsupported field type")]
-    UnsupportedField(GenField),
+    UnsupportedField(Box<GenField>),
+    #[error("Duplicate sort column name")]
+    DuplicateSortColumn(String),
 }
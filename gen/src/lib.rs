@@ -13,26 +13,31 @@ pub mod schema;
 mod test_code;
 mod types;
 mod util;
+mod view_code;
 
 use error::Error;
 use schema::{GenSchema, GenStruct};
 
+// The flags are independent switches that are set directly in build script struct literals.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Config {
-    pub base_derives: Vec<&'static str>,
+    pub base_derives: Vec<String>,
+    pub derived_sort_columns: bool,
     pub format: bool,
     pub serde_support: bool,
     pub tests: bool,
+    pub views: bool,
 }
 
 impl Config {
     #[must_use]
-    pub fn derives(&self) -> Vec<&'static str> {
+    pub fn derives(&self) -> Vec<String> {
         let mut derives = self.base_derives.clone();
 
         if self.serde_support {
-            derives.push("serde::Deserialize");
-            derives.push("serde::Serialize");
+            derives.push("serde::Deserialize".to_string());
+            derives.push("serde::Serialize".to_string());
         }
 
         derives
@@ -41,13 +46,18 @@ impl Config {
 
 impl Default for Config {
     fn default() -> Self {
-        let base_derives = vec!["Clone", "Copy", "Debug", "Eq", "PartialEq"];
+        let base_derives = ["Clone", "Copy", "Debug", "Eq", "PartialEq"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
 
         Self {
             base_derives,
+            derived_sort_columns: false,
             format: true,
             serde_support: true,
             tests: true,
+            views: false,
         }
     }
 }
@@ -64,15 +74,7 @@ pub struct ParsedFileSchema {
 
 impl ParsedFileSchema {
     pub fn code(&self) -> Result<String, Error> {
-        let raw_code = self.scope.to_string();
-
-        if self.config.format {
-            let file =
-                syn::parse_file(&format!("#![cfg_attr(rustfmt, rustfmt_skip)]\n{raw_code}"))?;
-            Ok(prettyplease::unparse(&file))
-        } else {
-            Ok(raw_code)
-        }
+        format_code(self.scope.to_string(), self.config.format)
     }
 
     pub fn open<P: AsRef<Path>>(input: P, config: Config) -> Result<Self, Error> {
@@ -144,6 +146,23 @@ impl ParsedFileSchema {
     }
 }
 
+/// Generate only the struct definitions for a schema (without column information or any trait implementations).
+pub fn struct_code(schema: &GenSchema) -> Result<String, Error> {
+    let mut scope = Scope::new();
+    add_structs(&mut scope, schema);
+
+    format_code(scope.to_string(), schema.config.format)
+}
+
+fn format_code(raw_code: String, format: bool) -> Result<String, Error> {
+    if format {
+        let file = syn::parse_file(&format!("#![cfg_attr(rustfmt, rustfmt_skip)]\n{raw_code}"))?;
+        Ok(prettyplease::unparse(&file))
+    } else {
+        Ok(raw_code)
+    }
+}
+
 pub fn parse_schema(
     schema_source: &str,
     config: Config,
@@ -166,16 +185,7 @@ const STATIC_SCHEMA_DEF: &str = "
         ));
 ";
 
-fn schema_to_scope(
-    schema_source: &str,
-    schema: &GenSchema,
-    descriptor: &SchemaDescriptor,
-) -> Result<Scope, Error> {
-    let mut scope = Scope::new();
-
-    scope.raw(format!("const SCHEMA_SOURCE: &str = \"{schema_source}\";",));
-    scope.raw(STATIC_SCHEMA_DEF);
-
+fn add_structs(scope: &mut Scope, schema: &GenSchema) {
     for GenStruct {
         type_name,
         fields,
@@ -197,9 +207,46 @@ fn schema_to_scope(
             }
         }
     }
+}
+
+fn schema_to_scope(
+    schema_source: &str,
+    schema: &GenSchema,
+    descriptor: &SchemaDescriptor,
+) -> Result<Scope, Error> {
+    let mut scope = Scope::new();
+
+    scope.raw(format!("const SCHEMA_SOURCE: &str = \"{schema_source}\";",));
+    scope.raw(STATIC_SCHEMA_DEF);
+
+    add_structs(&mut scope, schema);
+
+    column_code::add_column_info_modules(
+        &mut scope,
+        &schema.gen_columns(),
+        &schema.derived_sort_columns(),
+    )?;
+
+    add_schema_impl(&mut scope, schema)?;
+    add_writer(&mut scope, schema)?;
+    add_base_impls(&mut scope, schema, descriptor)?;
+
+    code::add_workspace_struct(&mut scope, descriptor.columns())?;
+
+    if schema.config.views {
+        view_code::add_view_code(&mut scope, schema, descriptor.columns())?;
+    }
+
+    if schema.config.tests {
+        let test_module = scope.new_module("test").attr("cfg(test)");
 
-    column_code::add_column_info_modules(&mut scope, &schema.gen_columns());
+        test_code::gen_test_code(test_module, schema)?;
+    }
+
+    Ok(scope)
+}
 
+fn add_schema_impl(scope: &mut Scope, schema: &GenSchema) -> Result<(), Error> {
     let schema_impl = scope
         .new_impl(&schema.type_name)
         .impl_trait("parquetry::Schema")
@@ -212,7 +259,7 @@ fn schema_to_scope(
     schema_impl
         .new_fn("sort_key_value")
         .arg_ref_self()
-        .arg("sort_key", "parquetry::sort::SortKey<Self::SortColumn>")
+        .arg("sort_key", "&parquetry::sort::SortKey<Self::SortColumn>")
         .ret("Vec<u8>")
         .push_block(code::gen_sort_key_value_block());
 
@@ -234,6 +281,10 @@ fn schema_to_scope(
         .ret("Result<Self::Writer<W>, parquetry::error::Error>")
         .push_block(code::gen_writer_block()?);
 
+    Ok(())
+}
+
+fn add_writer(scope: &mut Scope, schema: &GenSchema) -> Result<(), Error> {
     let writer_struct = scope
         .new_struct(format!("{}Writer", schema.type_name))
         .vis("pub")
@@ -288,6 +339,14 @@ fn schema_to_scope(
         .ret("Result<parquet::file::metadata::ParquetMetaData, parquetry::error::Error>")
         .line("Ok(self.writer.close()?)");
 
+    Ok(())
+}
+
+fn add_base_impls(
+    scope: &mut Scope,
+    schema: &GenSchema,
+    descriptor: &SchemaDescriptor,
+) -> Result<(), Error> {
     let row_conversion_impl = scope
         .new_impl(&schema.type_name)
         .impl_trait("TryFrom<parquet::record::Row>")
@@ -344,13 +403,105 @@ fn schema_to_scope(
         code::gen_constructor(&gen_struct, base_impl.new_fn("new"))?;
     }
 
-    code::add_workspace_struct(&mut scope, descriptor.columns())?;
+    Ok(())
+}
 
-    if schema.config.tests {
-        let test_module = scope.new_module("test").attr("cfg(test)");
+#[cfg(test)]
+mod test {
+    use super::{Config, error::Error, parse_schema, schema_to_scope};
 
-        test_code::gen_test_code(test_module, schema)?;
+    const LISTS_SCHEMA: &str = "message lists {
+        required group tags (list) {
+            repeated group list {
+                required int32 element;
+            }
+        }
+        required group a {
+            optional group labels (list) {
+                repeated group list {
+                    optional binary element (string);
+                }
+            }
+        }
+    }";
+
+    const LIST_AND_COLUMN_SCHEMA: &str = "message list_and_column {
+        required group tags (list) {
+            repeated group list {
+                required int32 element;
+            }
+        }
+        required int32 tags_first;
+    }";
+
+    const NESTED_LISTS_SCHEMA: &str = "message nested_lists {
+        required group a {
+            required group tags (list) {
+                repeated group list {
+                    required int32 element;
+                }
+            }
+        }
+        required group b {
+            required group tags (list) {
+                repeated group list {
+                    required int32 element;
+                }
+            }
+        }
+    }";
+
+    fn generate(schema_source: &str, config: Config) -> Result<String, Error> {
+        let (schema, descriptor) = parse_schema(schema_source, config)?;
+
+        Ok(schema_to_scope(schema_source, &schema, &descriptor)?.to_string())
     }
 
-    Ok(scope)
+    fn derived_config() -> Config {
+        Config {
+            derived_sort_columns: true,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn derived_sort_columns_disabled_by_default() -> Result<(), Error> {
+        let code = generate(LISTS_SCHEMA, Config::default())?;
+
+        assert!(!code.contains("TagsFirst"));
+        assert!(!code.contains("LabelsLength"));
+
+        // Columns whose names would collide with derived columns are allowed if derived columns are disabled.
+        let code = generate(LIST_AND_COLUMN_SCHEMA, Config::default())?;
+
+        assert!(code.contains("TagsFirst"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn derived_sort_columns_enabled() -> Result<(), Error> {
+        let code = generate(LISTS_SCHEMA, derived_config())?;
+
+        for expression in ["First", "Last", "Min", "Max", "Length"] {
+            assert!(code.contains(&format!("Tags{expression}")));
+            assert!(code.contains(&format!("Labels{expression}")));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn derived_sort_column_collision_with_column() {
+        let result = generate(LIST_AND_COLUMN_SCHEMA, derived_config());
+
+        assert!(matches!(result, Err(Error::DuplicateSortColumn(name)) if name == "TagsFirst"));
+    }
+
+    #[test]
+    fn derived_sort_column_collision_across_structs() {
+        let result = generate(NESTED_LISTS_SCHEMA, derived_config());
+
+        assert!(matches!(result, Err(Error::DuplicateSortColumn(name)) if name == "TagsFirst"));
+    }
 }
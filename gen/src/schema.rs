@@ -47,7 +47,7 @@ pub enum GenType {
 pub struct GenStruct {
     pub type_name: String,
     pub fields: Vec<GenField>,
-    pub derives: Vec<&'static str>,
+    pub derives: Vec<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -58,17 +58,36 @@ pub struct GenColumn {
     pub mapping: TypeMapping,
 }
 
+/// A sort expression over the elements of a list of primitive values.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SortExpression {
+    First,
+    Last,
+    Min,
+    Max,
+    Length,
+}
+
+/// A derived sort column for a list field that is not contained in another list.
+#[derive(Clone, Debug)]
+pub struct DerivedSortColumn {
+    pub list_path: Vec<(String, bool)>,
+    pub element_optional: bool,
+    pub gen_column: GenColumn,
+    pub expression: SortExpression,
+}
+
 impl GenStruct {
     fn new(
         type_name: &str,
         fields: Vec<GenField>,
-        base_derives: &[&'static str],
+        base_derives: &[String],
         disallowed_derives: HashSet<&str>,
     ) -> Self {
         let derives = base_derives
             .iter()
-            .copied()
-            .filter(|value| !disallowed_derives.contains(value))
+            .filter(|value| !disallowed_derives.contains(value.as_str()))
+            .cloned()
             .collect::<Vec<_>>();
 
         Self {
@@ -151,6 +170,22 @@ impl GenSchema {
 
         gen_columns
     }
+
+    /// The derived sort columns for lists of primitive values (empty unless enabled in the configuration).
+    #[must_use]
+    pub fn derived_sort_columns(&self) -> Vec<DerivedSortColumn> {
+        let mut derived_sort_columns = vec![];
+
+        if !self.config.derived_sort_columns {
+            return derived_sort_columns;
+        }
+
+        for gen_field in &self.gen_fields {
+            gen_field.derived_sort_columns(vec![], &mut derived_sort_columns);
+        }
+
+        derived_sort_columns
+    }
 }
 
 impl GenField {
@@ -163,6 +198,39 @@ impl GenField {
         }
     }
 
+    fn derived_sort_columns(
+        &self,
+        mut path: Vec<(String, bool)>,
+        acc: &mut Vec<DerivedSortColumn>,
+    ) {
+        path.push((self.name.clone(), self.optional));
+
+        match &self.gen_type {
+            GenType::Column(_) => {}
+            GenType::Struct { gen_fields, .. } => {
+                for gen_field in gen_fields {
+                    gen_field.derived_sort_columns(path.clone(), acc);
+                }
+            }
+            GenType::List {
+                element_optional,
+                element_gen_type,
+                ..
+            } => {
+                if let GenType::Column(gen_column) = element_gen_type.as_ref() {
+                    for expression in SortExpression::VALUES {
+                        acc.push(DerivedSortColumn {
+                            list_path: path.clone(),
+                            element_optional: *element_optional,
+                            gen_column: gen_column.clone(),
+                            expression,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
     fn field_name(source_name: &str) -> String {
         source_name.to_string()
     }
@@ -389,7 +457,7 @@ impl GenType {
         values
     }
 
-    fn structs(&self, type_name: &str, base_derives: &[&'static str], acc: &mut Vec<GenStruct>) {
+    fn structs(&self, type_name: &str, base_derives: &[String], acc: &mut Vec<GenStruct>) {
         match self {
             Self::Column { .. } => {}
             Self::Struct { gen_fields, .. } => {
@@ -436,6 +504,31 @@ impl GenType {
     }
 }
 
+impl SortExpression {
+    pub const VALUES: [Self; 5] = [Self::First, Self::Last, Self::Min, Self::Max, Self::Length];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::First => "First",
+            Self::Last => "Last",
+            Self::Min => "Min",
+            Self::Max => "Max",
+            Self::Length => "Length",
+        }
+    }
+}
+
+impl DerivedSortColumn {
+    #[must_use]
+    pub fn variant_name(&self) -> String {
+        format!(
+            "{}{}",
+            self.list_path.last().unwrap().0.to_case(Case::Pascal),
+            self.expression.name()
+        )
+    }
+}
+
 impl GenColumn {
     #[must_use]
     pub fn variant_name(&self) -> String {
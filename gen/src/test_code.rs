@@ -39,6 +39,12 @@ pub fn gen_test_code(test_module: &mut Module, schema: &GenSchema) -> Result<(),
         test_module.scope().raw(line);
     }
 
+    if schema.config.views {
+        for line in gen_round_trip_view(&schema.type_name) {
+            test_module.scope().raw(line);
+        }
+    }
+
     test_module
         .new_fn("gen_valid_date")
         .arg("g", "&mut quickcheck::Gen")
@@ -237,3 +243,36 @@ fn gen_round_trip_write(type_name: &str) -> Vec<String> {
         "}".to_string(),
     ]
 }
+
+fn gen_round_trip_view(type_name: &str) -> Vec<String> {
+    vec![
+        format!("fn round_trip_view_impl(groups: Vec<Vec<super::{type_name}>>) -> bool {{"),
+        format!(
+            "let test_dir = tempfile::Builder::new().prefix(\"{type_name}-view-data\").tempdir().unwrap();"
+        ),
+        "let test_file_path = test_dir.path().join(\"view-data.parquet\");".to_string(),
+        "let test_file = std::fs::File::create(&test_file_path).unwrap();".to_string(),
+        format!(
+            "<super::{type_name} as parquetry::Schema>::write_row_groups(test_file, Default::default(), groups.clone()).unwrap();"
+        ),
+        "let read_file = std::fs::File::open(test_file_path).unwrap();".to_string(),
+        "let read_options = parquet::file::serialized_reader::ReadOptionsBuilder::new().build();"
+            .to_string(),
+        format!(
+            "let mut reader = parquetry::view::BatchReader::<super::{type_name}, _>::new(read_file, read_options).unwrap();"
+        ),
+        "let mut read_values = vec![];".to_string(),
+        "while let Some(values) = reader.next_batch().unwrap() {".to_string(),
+        "for value in values {".to_string(),
+        "read_values.push(value.unwrap().to_owned());".to_string(),
+        "}".to_string(),
+        "}".to_string(),
+        "read_values == groups.into_iter().flatten().collect::<Vec<_>>()".to_string(),
+        "}".to_string(),
+        "quickcheck::quickcheck! {".to_string(),
+        format!("    fn round_trip_view(groups: Vec<Vec<super::{type_name}>>) -> bool {{"),
+        "        round_trip_view_impl(groups)".to_string(),
+        "    }".to_string(),
+        "}".to_string(),
+    ]
+}
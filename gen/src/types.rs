@@ -112,6 +112,20 @@ impl TypeMapping {
         }
     }
 
+    /// The field type used in generated borrowed views.
+    pub fn ref_type_name(&self) -> String {
+        match self {
+            Self::String => "&'a str".to_string(),
+            Self::ByteArray => "&'a [u8]".to_string(),
+            other => other.rust_type_name(),
+        }
+    }
+
+    /// Whether the field type used in generated borrowed views borrows from the column buffers.
+    pub const fn is_borrowed_in_ref(&self) -> bool {
+        matches!(self, Self::String | Self::ByteArray)
+    }
+
     pub fn physical_type_conversion(&self, name: &str) -> String {
         match self {
             Self::Bool | Self::I32 | Self::I64 | Self::F32 | Self::F64 => format!("*{name}"),
@@ -175,6 +189,29 @@ impl TypeMapping {
         }
     }
 
+    /// Convert a reference to a decoded physical value into the field type used in generated borrowed views.
+    pub fn ref_value_conversion(&self, field_name: &str, binding_name: &str) -> String {
+        match self {
+            Self::U32 => format!("*{binding_name} as u32"),
+            Self::U64 => format!("*{binding_name} as u64"),
+            Self::String => format!(
+                "std::str::from_utf8({binding_name}.data()).map_err(|_| {})?",
+                Self::error(field_name)
+            ),
+            Self::ByteArray => format!("{binding_name}.data()"),
+            other => other.row_field_conversion(field_name, binding_name),
+        }
+    }
+
+    /// Convert a reference to a borrowed view field value into the owned field type.
+    pub fn owned_from_ref_conversion(&self, binding_name: &str) -> String {
+        match self {
+            Self::String => format!("{binding_name}.to_string()"),
+            Self::ByteArray => format!("{binding_name}.to_vec()"),
+            _ => format!("*{binding_name}"),
+        }
+    }
+
     pub fn disallowed_derives(&self) -> Vec<&'static str> {
         match self {
             Self::String | Self::ByteArray => vec!["Copy"],
@@ -204,33 +241,27 @@ impl TypeMapping {
             Self::Bool => {
                 code.push_str("bytes.push(if column.descending { if value { 0 } else { 1 } } else { if value { 1 } else { 0 } });");
             }
-            Self::I32 | Self::I64 | Self::U32 | Self::U64 | Self::F32 | Self::F64 => {
-                code.push_str("for b in value.to_be_bytes() {");
-                code.push_str("bytes.push(if column.descending { !b } else { b });");
-                code.push('}');
-            }
-            Self::Date => {
-                code.push_str(&format!(
-                    "for b in (value.signed_duration_since({EPOCH_DATE}).num_days() as i32).to_be_bytes() {{",
-                ));
-                code.push_str("bytes.push(if column.descending { !b } else { b });");
-                code.push('}');
-            }
-            Self::DateTime(_) => {
-                code.push_str("for b in value.timestamp_micros().to_be_bytes() {");
-                code.push_str("bytes.push(if column.descending { !b } else { b });");
-                code.push('}');
+            Self::I32
+            | Self::I64
+            | Self::U32
+            | Self::U64
+            | Self::F32
+            | Self::F64
+            | Self::Date
+            | Self::DateTime(_) => {
+                code.push_str(
+                    "parquetry::sort::SortValue::write_sort_bytes(&value, column.descending, column.nulls_first, bytes);",
+                );
             }
             Self::String => {
-                code.push_str("for b in value.as_bytes() {");
-                code.push_str("bytes.push(if column.descending { !b } else { *b });");
-                code.push('}');
-                code.push_str("bytes.push(if column.descending { u8::MAX } else { b'\\0' });");
+                code.push_str(
+                    "parquetry::sort::write_escaped_bytes(value.as_bytes(), column.descending, bytes);",
+                );
             }
             Self::ByteArray => {
-                code.push_str("for b in value {");
-                code.push_str("bytes.push(if column.descending { !b } else { *b });");
-                code.push('}');
+                code.push_str(
+                    "parquetry::sort::write_escaped_bytes(value, column.descending, bytes);",
+                );
             }
             Self::FixedLengthByteArray(_) => {
                 code.push_str("for b in value {");
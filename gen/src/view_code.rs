@@ -0,0 +1,475 @@
+use codegen::{Block, Scope};
+use parquet::schema::types::ColumnDescPtr;
+
+use super::{
+    code::{self, WORKSPACE_STRUCT_NAME},
+    error::Error,
+    schema::{GenColumn, GenField, GenSchema, GenStruct, GenType},
+};
+
+pub const CURSOR_STRUCT_NAME: &str = "ParquetryCursor";
+
+const LIFETIME_MARKER_FIELD: &str = "_lifetime";
+
+/// Derives for the generated borrowed views (which also derive `Copy` if they do not contain lists).
+const REF_DERIVES: [&str; 3] = ["Clone", "Debug", "PartialEq"];
+
+pub fn add_view_code(
+    scope: &mut Scope,
+    schema: &GenSchema,
+    columns: &[ColumnDescPtr],
+) -> Result<(), Error> {
+    for gen_struct in schema.structs() {
+        add_ref_struct(scope, &gen_struct);
+    }
+
+    add_batch_structs(scope, schema, columns);
+
+    let view_impl = scope
+        .new_impl(&schema.type_name)
+        .impl_trait("parquetry::view::SchemaView")
+        .associate_type(
+            "Ref<'a>",
+            format!("{}<'a>", ref_type_name(&schema.type_name)),
+        )
+        .associate_type("Batch", batch_type_name(&schema.type_name))
+        .associate_type(
+            "RefIter<'a>",
+            format!("{}<'a>", ref_iter_type_name(&schema.type_name)),
+        );
+
+    view_impl
+        .new_fn("read_batch")
+        .arg(
+            "row_group_reader",
+            "&dyn parquet::file::reader::RowGroupReader",
+        )
+        .arg("batch", "&mut Self::Batch")
+        .ret("Result<usize, parquetry::error::Error>")
+        .push_block(gen_read_batch_block(columns)?);
+
+    view_impl
+        .new_fn("batch_refs")
+        .arg("batch", "&Self::Batch")
+        .ret("Self::RefIter<'_>")
+        .line(format!(
+            "{} {{ workspace: &batch.workspace, cursor: Default::default(), remaining: batch.num_rows }}",
+            ref_iter_type_name(&schema.type_name)
+        ));
+
+    scope
+        .new_impl(&schema.type_name)
+        .new_fn("read_ref")
+        .generic("'a")
+        .arg("workspace", format!("&'a {WORKSPACE_STRUCT_NAME}"))
+        .arg("cursor", format!("&mut {CURSOR_STRUCT_NAME}"))
+        .ret(format!(
+            "Result<{}<'a>, parquetry::error::Error>",
+            ref_type_name(&schema.type_name)
+        ))
+        .line(format!(
+            "Ok({})",
+            read_struct_expr(&schema.type_name, &schema.gen_fields)
+        ));
+
+    Ok(())
+}
+
+fn add_ref_struct(scope: &mut Scope, gen_struct: &GenStruct) {
+    let ref_struct = scope
+        .new_struct(ref_type_name(&gen_struct.type_name))
+        .vis("pub")
+        .generic("'a");
+
+    for value in REF_DERIVES {
+        ref_struct.derive(value);
+    }
+
+    if !gen_struct
+        .fields
+        .iter()
+        .any(|gen_field| contains_list(&gen_field.gen_type))
+    {
+        ref_struct.derive("Copy");
+    }
+
+    for gen_field in &gen_struct.fields {
+        ref_struct
+            .new_field(&gen_field.name, field_ref_type_name(gen_field))
+            .vis("pub");
+    }
+
+    if needs_lifetime_marker(&gen_struct.fields) {
+        ref_struct.new_field(LIFETIME_MARKER_FIELD, "std::marker::PhantomData<&'a ()>");
+    }
+
+    let to_owned = scope
+        .new_impl(format!("{}<'a>", ref_type_name(&gen_struct.type_name)))
+        .generic("'a")
+        .new_fn("to_owned")
+        .vis("pub")
+        .arg_ref_self()
+        .ret(&gen_struct.type_name);
+
+    to_owned.line(format!(
+        "let {} {{ {}, .. }} = self;",
+        ref_type_name(&gen_struct.type_name),
+        gen_struct
+            .fields
+            .iter()
+            .map(|gen_field| gen_field.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    to_owned.line(format!("{} {{", gen_struct.type_name));
+
+    for gen_field in &gen_struct.fields {
+        to_owned.line(format!(
+            "{}: {},",
+            gen_field.name,
+            owned_conversion(
+                &gen_field.name,
+                &gen_field.gen_type,
+                &gen_field.base_type_name,
+                gen_field.optional
+            )
+        ));
+    }
+
+    to_owned.line("}");
+}
+
+fn add_batch_structs(scope: &mut Scope, schema: &GenSchema, columns: &[ColumnDescPtr]) {
+    let cursor = scope.new_struct(CURSOR_STRUCT_NAME).derive("Default");
+
+    for index in 0..columns.len() {
+        cursor.new_field(levels_cursor_name(index), "usize");
+        cursor.new_field(values_cursor_name(index), "usize");
+    }
+
+    let batch_struct = scope
+        .new_struct(batch_type_name(&schema.type_name))
+        .vis("pub")
+        .derive("Default");
+
+    batch_struct.new_field("workspace", WORKSPACE_STRUCT_NAME);
+    batch_struct.new_field("num_rows", "usize");
+
+    let ref_iter_struct = scope
+        .new_struct(ref_iter_type_name(&schema.type_name))
+        .vis("pub")
+        .generic("'a");
+
+    ref_iter_struct.new_field("workspace", format!("&'a {WORKSPACE_STRUCT_NAME}"));
+    ref_iter_struct.new_field("cursor", CURSOR_STRUCT_NAME);
+    ref_iter_struct.new_field("remaining", "usize");
+
+    let ref_iter_next = scope
+        .new_impl(format!("{}<'a>", ref_iter_type_name(&schema.type_name)))
+        .generic("'a")
+        .impl_trait("Iterator")
+        .associate_type(
+            "Item",
+            format!(
+                "Result<{}<'a>, parquetry::error::Error>",
+                ref_type_name(&schema.type_name)
+            ),
+        )
+        .new_fn("next")
+        .arg_mut_self()
+        .ret("Option<Self::Item>");
+
+    ref_iter_next.line("if self.remaining == 0 {");
+    ref_iter_next.line("None");
+    ref_iter_next.line("} else {");
+    ref_iter_next.line(format!(
+        "let result = {}::read_ref(self.workspace, &mut self.cursor);",
+        schema.type_name
+    ));
+    ref_iter_next.line("self.remaining = if result.is_ok() { self.remaining - 1 } else { 0 };");
+    ref_iter_next.line("Some(result)");
+    ref_iter_next.line("}");
+}
+
+fn ref_type_name(type_name: &str) -> String {
+    format!("{type_name}Ref")
+}
+
+fn batch_type_name(type_name: &str) -> String {
+    format!("{type_name}Batch")
+}
+
+fn ref_iter_type_name(type_name: &str) -> String {
+    format!("{type_name}RefIter")
+}
+
+fn levels_cursor_name(index: usize) -> String {
+    format!("levels_{index:04}")
+}
+
+fn values_cursor_name(index: usize) -> String {
+    format!("values_{index:04}")
+}
+
+fn field_ref_type_name(gen_field: &GenField) -> String {
+    gen_type_ref_type_name(
+        &gen_field.gen_type,
+        &gen_field.base_type_name,
+        gen_field.optional,
+    )
+}
+
+fn gen_type_ref_type_name(gen_type: &GenType, base_type_name: &str, optional: bool) -> String {
+    let type_name = match gen_type {
+        GenType::Column(GenColumn { mapping, .. }) => mapping.ref_type_name(),
+        GenType::Struct { .. } => format!("{}<'a>", ref_type_name(base_type_name)),
+        GenType::List {
+            element_optional,
+            element_gen_type,
+            element_struct_name,
+            ..
+        } => format!(
+            "Vec<{}>",
+            gen_type_ref_type_name(element_gen_type, element_struct_name, *element_optional)
+        ),
+    };
+
+    if optional {
+        format!("Option<{type_name}>")
+    } else {
+        type_name
+    }
+}
+
+/// Whether none of the fields borrow from the column buffers (in which case the lifetime would be unused).
+fn needs_lifetime_marker(gen_fields: &[GenField]) -> bool {
+    !gen_fields
+        .iter()
+        .any(|gen_field| is_borrowed_in_ref(&gen_field.gen_type))
+}
+
+fn is_borrowed_in_ref(gen_type: &GenType) -> bool {
+    match gen_type {
+        GenType::Column(GenColumn { mapping, .. }) => mapping.is_borrowed_in_ref(),
+        GenType::Struct { .. } => true,
+        GenType::List {
+            element_gen_type, ..
+        } => is_borrowed_in_ref(element_gen_type),
+    }
+}
+
+/// Whether the field type used in generated borrowed views contains a vector.
+fn contains_list(gen_type: &GenType) -> bool {
+    match gen_type {
+        GenType::Column(_) => false,
+        GenType::Struct { gen_fields, .. } => gen_fields
+            .iter()
+            .any(|gen_field| contains_list(&gen_field.gen_type)),
+        GenType::List { .. } => true,
+    }
+}
+
+/// Generate an expression converting the given reference to a view field value into the owned field value.
+fn owned_conversion(
+    binding_name: &str,
+    gen_type: &GenType,
+    base_type_name: &str,
+    optional: bool,
+) -> String {
+    if optional {
+        format!(
+            "{binding_name}.as_ref().map(|value| {})",
+            owned_conversion("value", gen_type, base_type_name, false)
+        )
+    } else {
+        match gen_type {
+            GenType::Column(GenColumn { mapping, .. }) => {
+                mapping.owned_from_ref_conversion(binding_name)
+            }
+            GenType::Struct { .. } => {
+                format!(
+                    "{}::to_owned({binding_name})",
+                    ref_type_name(base_type_name)
+                )
+            }
+            GenType::List {
+                element_optional,
+                element_gen_type,
+                element_struct_name,
+                ..
+            } => format!(
+                "{binding_name}.iter().map(|element| {}).collect()",
+                owned_conversion(
+                    "element",
+                    element_gen_type,
+                    element_struct_name,
+                    *element_optional
+                )
+            ),
+        }
+    }
+}
+
+fn read_struct_expr(type_name: &str, gen_fields: &[GenField]) -> String {
+    let mut fields = gen_fields
+        .iter()
+        .map(|gen_field| {
+            format!(
+                "{}: {}",
+                gen_field.name,
+                read_expr(
+                    &gen_field.gen_type,
+                    &gen_field.name,
+                    &gen_field.base_type_name,
+                    gen_field.optional
+                )
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if needs_lifetime_marker(gen_fields) {
+        fields.push(format!("{LIFETIME_MARKER_FIELD}: std::marker::PhantomData"));
+    }
+
+    format!("{} {{ {} }}", ref_type_name(type_name), fields.join(", "))
+}
+
+fn level_expr(levels_var_name: &str, index: usize) -> String {
+    format!(
+        "parquetry::view::level(&workspace.{levels_var_name}, cursor.{}, {index})",
+        levels_cursor_name(index)
+    )
+}
+
+fn skip_code(gen_type: &GenType) -> String {
+    gen_type
+        .column_indices()
+        .map(|index| format!("cursor.{} += 1;", levels_cursor_name(index)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Generate an expression that reads the next value for this field and advances the cursor.
+///
+/// This assumes that all of the field's ancestors are present, and relies on the level encoding used in
+/// `code::gen_type_writer_code`.
+fn read_expr(gen_type: &GenType, field_name: &str, base_type_name: &str, optional: bool) -> String {
+    match gen_type {
+        GenType::Column(GenColumn {
+            index,
+            descriptor,
+            mapping,
+            ..
+        }) => {
+            let levels_cursor = levels_cursor_name(*index);
+            let values_cursor = values_cursor_name(*index);
+            let value_code = format!(
+                "let value = parquetry::view::value(&workspace.{}, cursor.{values_cursor}, {index})?; cursor.{values_cursor} += 1;",
+                code::values_var_name(*index)
+            );
+            let conversion = mapping.ref_value_conversion(field_name, "value");
+
+            if optional {
+                format!(
+                    "{{ let present = {}? == {}; cursor.{levels_cursor} += 1; if present {{ {value_code} Some({conversion}) }} else {{ None }} }}",
+                    level_expr(&code::def_levels_var_name(*index), *index),
+                    descriptor.max_def_level()
+                )
+            } else {
+                format!("{{ cursor.{levels_cursor} += 1; {value_code} {conversion} }}")
+            }
+        }
+        GenType::Struct {
+            gen_fields,
+            def_depth,
+            ..
+        } => {
+            let value = read_struct_expr(base_type_name, gen_fields);
+
+            if optional {
+                let first_index = gen_type.column_indices().start;
+
+                format!(
+                    "if {}? >= {def_depth} {{ Some({value}) }} else {{ {} None }}",
+                    level_expr(&code::def_levels_var_name(first_index), first_index),
+                    skip_code(gen_type)
+                )
+            } else {
+                value
+            }
+        }
+        GenType::List {
+            def_depth,
+            rep_depth,
+            element_optional,
+            element_gen_type,
+            element_struct_name,
+        } => {
+            let first_index = gen_type.column_indices().start;
+            let element = read_expr(
+                element_gen_type,
+                field_name,
+                element_struct_name,
+                *element_optional,
+            );
+            let non_empty = format!(
+                "{{ let mut values = vec![]; loop {{ values.push({element}); if {}.ok() != Some({rep_depth}) {{ break; }} }} values }}",
+                level_expr(&code::rep_levels_var_name(first_index), first_index)
+            );
+            let def = level_expr(&code::def_levels_var_name(first_index), first_index);
+            let skip = skip_code(gen_type);
+
+            if optional {
+                format!(
+                    "{{ let def = {def}?; if def >= {def_depth} {{ Some({non_empty}) }} else if def == {} {{ {skip} Some(vec![]) }} else {{ {skip} None }} }}",
+                    def_depth - 1
+                )
+            } else {
+                format!("if {def}? >= {def_depth} {{ {non_empty} }} else {{ {skip} vec![] }}")
+            }
+        }
+    }
+}
+
+fn gen_read_batch_block(columns: &[ColumnDescPtr]) -> Result<Block, Error> {
+    let mut block = Block::new("");
+    block.line("batch.workspace.clear();");
+    block.line("let num_rows = usize::try_from(row_group_reader.metadata().num_rows()).map_err(|_| parquetry::error::Error::InvalidField(\"num_rows\".to_string()))?;");
+
+    for (index, column) in columns.iter().enumerate() {
+        block.line(format!(
+            "parquet::column::reader::get_typed_column_reader::<parquet::data_type::{}>(row_group_reader.get_column_reader({index})?).read_records(",
+            code::physical_type_name(column.physical_type())?
+        ));
+        block.line("num_rows,");
+
+        if column.max_def_level() > 0 {
+            block.line(format!(
+                "Some(&mut batch.workspace.{}),",
+                code::def_levels_var_name(index)
+            ));
+        } else {
+            block.line("None,");
+        }
+
+        if column.max_rep_level() > 0 {
+            block.line(format!(
+                "Some(&mut batch.workspace.{}),",
+                code::rep_levels_var_name(index)
+            ));
+        } else {
+            block.line("None,");
+        }
+
+        block.line(format!(
+            "&mut batch.workspace.{},",
+            code::values_var_name(index)
+        ));
+        block.line(")?;");
+    }
+
+    block.line("batch.num_rows = num_rows;");
+    block.line("Ok(num_rows)");
+
+    Ok(block)
+}
@@ -0,0 +1,373 @@
+use crate::{ColumnInfo, error::Error};
+use chrono::{DateTime, NaiveDate, Utc};
+use parquet::{
+    basic::{LogicalType, TimeUnit, Type as PhysicalType},
+    column::reader::{ColumnReader, ColumnReaderImpl},
+    data_type::DataType,
+    file::{
+        metadata::ParquetMetaData,
+        reader::{ChunkReader, FileReader, RowGroupReader},
+        serialized_reader::SerializedFileReader,
+    },
+    record::reader::RowIter,
+    schema::types::{ColumnDescriptor, Type},
+};
+
+const EPOCH_DATE: NaiveDate = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+const BATCH_SIZE: usize = 1024;
+
+/// A Rust value that can be checked against a column's split-block bloom filter.
+///
+/// The bytes returned must be the plain encoding of the value for the column's physical type, since that is what the
+/// Parquet writer hashes when building the filter.
+pub trait BloomValue {
+    /// Returns `None` if the value's type does not correspond to the column's type.
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>>;
+}
+
+impl<T: BloomValue + ?Sized> BloomValue for &T {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        (*self).bloom_bytes(descriptor)
+    }
+}
+
+impl BloomValue for bool {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        (descriptor.physical_type() == PhysicalType::BOOLEAN).then(|| vec![u8::from(*self)])
+    }
+}
+
+macro_rules! numeric_bloom_value {
+    ($rust_type:ty, $physical_type:expr) => {
+        impl BloomValue for $rust_type {
+            fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+                (descriptor.physical_type() == $physical_type
+                    && !matches!(
+                        descriptor.logical_type_ref(),
+                        Some(LogicalType::Date | LogicalType::Timestamp { .. })
+                    ))
+                .then(|| self.to_le_bytes().to_vec())
+            }
+        }
+    };
+}
+
+// Unsigned values are stored as the signed type with the same bits, so the little-endian bytes are the same.
+numeric_bloom_value!(i32, PhysicalType::INT32);
+numeric_bloom_value!(u32, PhysicalType::INT32);
+numeric_bloom_value!(i64, PhysicalType::INT64);
+numeric_bloom_value!(u64, PhysicalType::INT64);
+numeric_bloom_value!(f32, PhysicalType::FLOAT);
+numeric_bloom_value!(f64, PhysicalType::DOUBLE);
+
+macro_rules! widened_bloom_value {
+    ($rust_type:ty) => {
+        impl BloomValue for $rust_type {
+            fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+                i32::from(*self).bloom_bytes(descriptor)
+            }
+        }
+    };
+}
+
+// 8 and 16-bit integers are stored as `INT32` values, so they are widened before being encoded.
+widened_bloom_value!(i8);
+widened_bloom_value!(i16);
+widened_bloom_value!(u8);
+widened_bloom_value!(u16);
+
+impl BloomValue for NaiveDate {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        if descriptor.physical_type() == PhysicalType::INT32
+            && matches!(descriptor.logical_type_ref(), Some(LogicalType::Date))
+        {
+            let days = i32::try_from(self.signed_duration_since(EPOCH_DATE).num_days()).ok()?;
+
+            Some(days.to_le_bytes().to_vec())
+        } else {
+            None
+        }
+    }
+}
+
+impl BloomValue for DateTime<Utc> {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        if descriptor.physical_type() == PhysicalType::INT64 {
+            match descriptor.logical_type_ref() {
+                Some(LogicalType::Timestamp {
+                    unit: TimeUnit::MILLIS,
+                    ..
+                }) => Some(self.timestamp_millis()),
+                Some(LogicalType::Timestamp {
+                    unit: TimeUnit::MICROS,
+                    ..
+                }) => Some(self.timestamp_micros()),
+                Some(LogicalType::Timestamp {
+                    unit: TimeUnit::NANOS,
+                    ..
+                }) => self.timestamp_nanos_opt(),
+                _ => None,
+            }
+            .map(|value| value.to_le_bytes().to_vec())
+        } else {
+            None
+        }
+    }
+}
+
+impl BloomValue for str {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        (descriptor.physical_type() == PhysicalType::BYTE_ARRAY).then(|| self.as_bytes().to_vec())
+    }
+}
+
+impl BloomValue for String {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        self.as_str().bloom_bytes(descriptor)
+    }
+}
+
+impl BloomValue for [u8] {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        match descriptor.physical_type() {
+            PhysicalType::BYTE_ARRAY => Some(self.to_vec()),
+            PhysicalType::FIXED_LEN_BYTE_ARRAY
+                if usize::try_from(descriptor.type_length()).ok() == Some(self.len()) =>
+            {
+                Some(self.to_vec())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl BloomValue for Vec<u8> {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        self.as_slice().bloom_bytes(descriptor)
+    }
+}
+
+impl<const N: usize> BloomValue for [u8; N] {
+    fn bloom_bytes(&self, descriptor: &ColumnDescriptor) -> Option<Vec<u8>> {
+        (descriptor.physical_type() == PhysicalType::FIXED_LEN_BYTE_ARRAY
+            && usize::try_from(descriptor.type_length()).ok() == Some(N))
+        .then(|| self.to_vec())
+    }
+}
+
+/// Return the indices of the row groups whose bloom filter for the given column might contain the value.
+///
+/// Row groups without a bloom filter for the column are always included. Note that the reader must have been opened
+/// with bloom filter reading enabled (see `ReaderPropertiesBuilder::set_read_bloom_filter`).
+pub fn matching_row_groups<F: FileReader + ?Sized, V: BloomValue + ?Sized>(
+    reader: &F,
+    column: &ColumnInfo,
+    value: &V,
+) -> Result<Vec<usize>, Error> {
+    let descriptor = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .column(column.index);
+    let bytes = value
+        .bloom_bytes(&descriptor)
+        .ok_or_else(|| Error::InvalidBloomFilterValue(column.path()))?;
+
+    let mut indices = vec![];
+
+    for index in 0..reader.num_row_groups() {
+        let row_group_reader = reader.get_row_group(index)?;
+
+        if row_group_reader
+            .get_column_bloom_filter(column.index)
+            .is_none_or(|filter| filter.check(bytes.as_slice()))
+        {
+            indices.push(index);
+        }
+    }
+
+    Ok(indices)
+}
+
+/// Return whether each row of the reader's row groups has a value in the given column that is equal to the value.
+///
+/// Values are compared by their plain encoding (see `BloomValue`), and a row of a repeated column matches if any of
+/// its values do.
+pub fn matching_rows<F: FileReader + ?Sized, V: BloomValue + ?Sized>(
+    reader: &F,
+    column: &ColumnInfo,
+    value: &V,
+) -> Result<Vec<bool>, Error> {
+    let descriptor = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .column(column.index);
+    let bytes = value
+        .bloom_bytes(&descriptor)
+        .ok_or_else(|| Error::InvalidBloomFilterValue(column.path()))?;
+    let bytes = bytes.as_slice();
+
+    let mut matches = vec![];
+
+    for index in 0..reader.num_row_groups() {
+        let row_group_reader = reader.get_row_group(index)?;
+
+        match row_group_reader.get_column_reader(column.index)? {
+            ColumnReader::BoolColumnReader(mut reader) => {
+                read_matches(&mut reader, &descriptor, &mut matches, |value| {
+                    [u8::from(*value)] == bytes
+                })
+            }
+            ColumnReader::Int32ColumnReader(mut reader) => {
+                read_matches(&mut reader, &descriptor, &mut matches, |value| {
+                    value.to_le_bytes() == bytes
+                })
+            }
+            ColumnReader::Int64ColumnReader(mut reader) => {
+                read_matches(&mut reader, &descriptor, &mut matches, |value| {
+                    value.to_le_bytes() == bytes
+                })
+            }
+            ColumnReader::FloatColumnReader(mut reader) => {
+                read_matches(&mut reader, &descriptor, &mut matches, |value| {
+                    value.to_le_bytes() == bytes
+                })
+            }
+            ColumnReader::DoubleColumnReader(mut reader) => {
+                read_matches(&mut reader, &descriptor, &mut matches, |value| {
+                    value.to_le_bytes() == bytes
+                })
+            }
+            ColumnReader::ByteArrayColumnReader(mut reader) => {
+                read_matches(&mut reader, &descriptor, &mut matches, |value| {
+                    value.data() == bytes
+                })
+            }
+            ColumnReader::FixedLenByteArrayColumnReader(mut reader) => {
+                read_matches(&mut reader, &descriptor, &mut matches, |value| {
+                    value.data() == bytes
+                })
+            }
+            // The code generator does not support `INT96`, so no value can match.
+            ColumnReader::Int96ColumnReader(_) => {
+                return Err(Error::InvalidBloomFilterValue(column.path()));
+            }
+        }?;
+    }
+
+    Ok(matches)
+}
+
+fn read_matches<T: DataType>(
+    reader: &mut ColumnReaderImpl<T>,
+    descriptor: &ColumnDescriptor,
+    matches: &mut Vec<bool>,
+    is_match: impl Fn(&T::T) -> bool,
+) -> Result<(), Error> {
+    let max_def_level = descriptor.max_def_level();
+    let max_rep_level = descriptor.max_rep_level();
+
+    let mut values = vec![];
+    let mut def_levels = vec![];
+    let mut rep_levels = vec![];
+
+    loop {
+        values.clear();
+        def_levels.clear();
+        rep_levels.clear();
+
+        let (records_read, _, levels_read) = reader.read_records(
+            BATCH_SIZE,
+            Some(&mut def_levels),
+            Some(&mut rep_levels),
+            &mut values,
+        )?;
+
+        if records_read == 0 && levels_read == 0 {
+            return Ok(());
+        }
+
+        let mut values = values.iter();
+
+        for level_index in 0..levels_read {
+            // Levels are not decoded for columns whose maximum level is zero.
+            let def_level = if max_def_level > 0 {
+                def_levels[level_index]
+            } else {
+                0
+            };
+            let rep_level = if max_rep_level > 0 {
+                rep_levels[level_index]
+            } else {
+                0
+            };
+
+            if rep_level == 0 || matches.is_empty() {
+                matches.push(false);
+            }
+
+            if def_level == max_def_level
+                && values.next().is_some_and(&is_match)
+                && let Some(last) = matches.last_mut()
+            {
+                *last = true;
+            }
+        }
+    }
+}
+
+/// A file reader that only exposes a subset of the row groups of the underlying file.
+pub struct RowGroupSubsetReader<R: ChunkReader> {
+    reader: SerializedFileReader<R>,
+    metadata: ParquetMetaData,
+    row_group_indices: Vec<usize>,
+}
+
+impl<R: ChunkReader + 'static> RowGroupSubsetReader<R> {
+    #[must_use]
+    pub fn new(reader: SerializedFileReader<R>, row_group_indices: Vec<usize>) -> Self {
+        let underlying = reader.metadata();
+        let metadata = ParquetMetaData::new(
+            underlying.file_metadata().clone(),
+            row_group_indices
+                .iter()
+                .map(|index| underlying.row_group(*index).clone())
+                .collect(),
+        );
+
+        Self {
+            reader,
+            metadata,
+            row_group_indices,
+        }
+    }
+
+    /// The indices of the exposed row groups in the underlying file.
+    #[must_use]
+    pub fn row_group_indices(&self) -> &[usize] {
+        &self.row_group_indices
+    }
+}
+
+impl<R: ChunkReader + 'static> FileReader for RowGroupSubsetReader<R> {
+    fn metadata(&self) -> &ParquetMetaData {
+        &self.metadata
+    }
+
+    fn num_row_groups(&self) -> usize {
+        self.row_group_indices.len()
+    }
+
+    fn get_row_group(&self, i: usize) -> parquet::errors::Result<Box<dyn RowGroupReader + '_>> {
+        let index = self.row_group_indices.get(i).ok_or_else(|| {
+            parquet::errors::ParquetError::IndexOutOfBound(i, self.row_group_indices.len())
+        })?;
+
+        self.reader.get_row_group(*index)
+    }
+
+    fn get_row_iter(&self, projection: Option<Type>) -> parquet::errors::Result<RowIter<'_>> {
+        RowIter::from_file(projection, self)
+    }
+}
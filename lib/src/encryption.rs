@@ -0,0 +1,134 @@
+use crate::{ColumnInfo, error::Error};
+use parquet::arrow::{
+    ArrowWriter,
+    arrow_reader::{ArrowReaderOptions, ParquetRecordBatchReaderBuilder},
+    arrow_writer::ArrowWriterOptions,
+};
+use parquet::encryption::{decrypt::FileDecryptionProperties, encrypt::FileEncryptionProperties};
+use parquet::errors::ParquetError;
+use parquet::file::{properties::WriterProperties, reader::ChunkReader};
+use std::sync::Arc;
+
+/// The AES keys used to encrypt the footer and (optionally) individual columns of a file.
+///
+/// Keys must be 16, 24, or 32 bytes long. If no column keys are provided, all columns are encrypted with the footer
+/// key; otherwise only the listed columns are encrypted.
+#[derive(Clone, Eq, PartialEq)]
+pub struct EncryptionKeys {
+    footer_key: Vec<u8>,
+    column_keys: Vec<(String, Vec<u8>)>,
+}
+
+impl std::fmt::Debug for EncryptionKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKeys")
+            .field("footer_key", &"<redacted>")
+            .field(
+                "column_keys",
+                &self
+                    .column_keys
+                    .iter()
+                    .map(|(column_name, _)| (column_name, "<redacted>"))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl EncryptionKeys {
+    #[must_use]
+    pub const fn new(footer_key: Vec<u8>) -> Self {
+        Self {
+            footer_key,
+            column_keys: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn with_column_key(mut self, column: &ColumnInfo, key: Vec<u8>) -> Self {
+        self.column_keys.push((column.path().string(), key));
+        self
+    }
+
+    pub fn encryption_properties(&self) -> Result<Arc<FileEncryptionProperties>, Error> {
+        let mut builder = FileEncryptionProperties::builder(self.footer_key.clone());
+
+        for (column_name, key) in &self.column_keys {
+            builder = builder.with_column_key(column_name, key.clone());
+        }
+
+        Ok(builder.build()?)
+    }
+
+    pub fn decryption_properties(&self) -> Result<Arc<FileDecryptionProperties>, Error> {
+        let mut builder = FileDecryptionProperties::builder(self.footer_key.clone());
+
+        for (column_name, key) in &self.column_keys {
+            builder = builder.with_column_key(column_name, key.clone());
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// Decrypt a file into an unencrypted in-memory copy with the same schema, key-value metadata, and row groups.
+///
+/// The parquet crate only supports decryption in its Arrow reader, so the file is decoded into record batches and
+/// re-encoded with its original Parquet schema, which the row-based reader can then read. This has some limitations:
+///
+/// * The entire decrypted file is held in memory, so memory use is proportional to the size of the file.
+/// * The decrypted values are stored in an unencrypted buffer that is not zeroed when it is dropped.
+/// * Each non-empty row group is written as a row group with the same rows, but empty row groups are dropped, and
+///   page boundaries, statistics, and bloom filters are those computed by the Arrow writer.
+pub(crate) fn decrypt<R: ChunkReader + 'static>(
+    reader: R,
+    keys: &EncryptionKeys,
+) -> Result<bytes::Bytes, Error> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        reader,
+        ArrowReaderOptions::new().with_file_decryption_properties(keys.decryption_properties()?),
+    )?;
+
+    let file_metadata = builder.metadata().file_metadata();
+    let options = ArrowWriterOptions::new()
+        .with_properties(
+            WriterProperties::builder()
+                .set_key_value_metadata(file_metadata.key_value_metadata().cloned())
+                .set_max_row_group_row_count(None)
+                .build(),
+        )
+        .with_parquet_schema(file_metadata.schema_descr().clone())
+        .with_skip_arrow_metadata(true);
+
+    // Record batches may span row groups, so we track the rows left in the current row group and flush at its end.
+    let mut row_group_sizes = builder
+        .metadata()
+        .row_groups()
+        .iter()
+        .map(|row_group| usize::try_from(row_group.num_rows()).unwrap_or_default())
+        .filter(|num_rows| *num_rows > 0)
+        .collect::<Vec<_>>()
+        .into_iter();
+    let mut remaining = row_group_sizes.next().unwrap_or(usize::MAX);
+
+    let mut writer = ArrowWriter::try_new_with_options(vec![], builder.schema().clone(), options)?;
+
+    for batch in builder.build()? {
+        let mut batch = batch.map_err(ParquetError::from)?;
+
+        while batch.num_rows() > 0 {
+            let len = remaining.min(batch.num_rows());
+
+            writer.write(&batch.slice(0, len))?;
+            batch = batch.slice(len, batch.num_rows() - len);
+            remaining -= len;
+
+            if remaining == 0 {
+                writer.flush()?;
+                remaining = row_group_sizes.next().unwrap_or(usize::MAX);
+            }
+        }
+    }
+
+    Ok(writer.into_inner()?.into())
+}
@@ -2,12 +2,25 @@ use parquet::schema::types::ColumnPath;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
     #[error("Parquet error")]
     Parquet(#[from] parquet::errors::ParquetError),
     #[error("Field error")]
     InvalidField(String),
     #[error("Oversized row value error")]
     OversizedRowValue { row_group_index: usize },
+    #[error("Value is out of sort order")]
+    UnsortedValue { row_index: usize },
+    #[error("Invalid bloom filter value")]
+    InvalidBloomFilterValue(ColumnPath),
+    #[error("Invalid column data")]
+    InvalidColumnData { column_index: usize },
+    #[error("Schema mismatch")]
+    SchemaMismatch {
+        expected: String,
+        found: Option<String>,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -21,8 +34,14 @@ pub enum ValueError {
 
 #[derive(thiserror::Error, Debug)]
 pub enum SortKeyError {
+    #[deprecated(note = "byte array columns are now supported at any position in a sort key")]
     #[error("Non-singleton byte array sort key")]
     NonSingletonByteArrayKey,
+    #[deprecated(note = "sort keys now support any non-zero number of columns (see `EmptySortKey`)")]
     #[error("Unsupported sort key length")]
     UnsupportedLength(usize),
+    #[error("Sort key must have at least one column")]
+    EmptySortKey,
+    #[error("Key prefix is longer than sort key")]
+    PrefixTooLong(usize),
 }
@@ -1,25 +1,32 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery, rust_2018_idioms)]
 #![allow(clippy::missing_errors_doc)]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "mmap"), forbid(unsafe_code))]
+#![cfg_attr(feature = "mmap", deny(unsafe_code))]
 use parquet::{
-    basic::LogicalType,
     file::{
         metadata::{ParquetMetaData, SortingColumn},
-        reader::ChunkReader,
-        serialized_reader::{ReadOptions, SerializedFileReader},
+        properties::ReaderProperties,
+        reader::{ChunkReader, FileReader},
+        serialized_reader::{ReadOptions, ReadOptionsBuilder, SerializedFileReader},
     },
     record::reader::RowIter,
     schema::types::{ColumnPath, SchemaDescPtr},
 };
-use std::marker::PhantomData;
+use std::{marker::PhantomData, path::Path};
 
+pub mod bloom;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod error;
+pub mod metadata;
+#[cfg(feature = "mmap")]
+pub mod mmap;
 pub mod read;
 pub mod sort;
+pub mod view;
 pub mod write;
 
 use crate::error::Error;
-use crate::sort::SortColumn;
 use crate::write::SchemaWrite;
 
 pub struct ColumnInfo {
@@ -61,39 +68,10 @@ pub trait Schema: Sized {
     where
         Self::SortColumn: sort::SortColumn + Copy,
     {
-        if columns.len() > 5 {
-            Err(error::SortKeyError::UnsupportedLength(columns.len()))
-        } else {
-            let schema = Self::schema();
-            let descriptors = schema.columns();
-
-            if columns.iter().any(|column| {
-                descriptors[column.column.index()].physical_type()
-                    == parquet::basic::Type::BYTE_ARRAY
-                    && !matches!(
-                        descriptors[column.column.index()].logical_type_ref(),
-                        Some(LogicalType::String)
-                    )
-            }) {
-                Err(error::SortKeyError::NonSingletonByteArrayKey)
-            } else {
-                match columns.len() {
-                    1 => Ok(sort::SortKey::Columns1(columns[0])),
-                    2 => Ok(sort::SortKey::Columns2(columns[0], columns[1])),
-                    3 => Ok(sort::SortKey::Columns3(columns[0], columns[1], columns[2])),
-                    4 => Ok(sort::SortKey::Columns4(
-                        columns[0], columns[1], columns[2], columns[3],
-                    )),
-                    5 => Ok(sort::SortKey::Columns5(
-                        columns[0], columns[1], columns[2], columns[3], columns[4],
-                    )),
-                    other => Err(error::SortKeyError::UnsupportedLength(other)),
-                }
-            }
-        }
+        sort::SortKey::new(columns).ok_or(error::SortKeyError::EmptySortKey)
     }
 
-    fn sort_key_value(&self, sort_key: sort::SortKey<Self::SortColumn>) -> Vec<u8>;
+    fn sort_key_value(&self, sort_key: &sort::SortKey<Self::SortColumn>) -> Vec<u8>;
 
     fn read<R: ChunkReader + 'static>(reader: R, options: ReadOptions) -> read::SchemaIter<Self> {
         match SerializedFileReader::new_with_options(reader, options) {
@@ -105,11 +83,164 @@ pub trait Schema: Sized {
         }
     }
 
+    /// Read from an in-memory buffer (this does not copy the buffer for `Vec<u8>`, `&'static [u8]`, or `Bytes`).
+    fn read_bytes<B: Into<bytes::Bytes>>(bytes: B, options: ReadOptions) -> read::SchemaIter<Self> {
+        Self::read(bytes.into(), options)
+    }
+
+    /// Read from a borrowed buffer.
+    ///
+    /// The row reader needs an owned buffer, so this copies the entire slice before reading. Use `read_bytes` instead
+    /// to avoid the copy if you already own the buffer.
+    #[must_use]
+    fn read_slice(bytes: &[u8], options: ReadOptions) -> read::SchemaIter<Self> {
+        Self::read(bytes::Bytes::copy_from_slice(bytes), options)
+    }
+
+    fn read_path<P: AsRef<Path>>(path: P, options: ReadOptions) -> read::SchemaIter<Self> {
+        match std::fs::File::open(path) {
+            Ok(file) => Self::read(file, options),
+            Err(error) => read::SchemaIter::Failed(Some(Error::from(error))),
+        }
+    }
+
+    /// Read from a memory-mapped file.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the file is not modified or truncated while the returned iterator is alive (see
+    /// `mmap::map_path`).
+    #[cfg(feature = "mmap")]
+    #[allow(unsafe_code)]
+    unsafe fn read_mmap<P: AsRef<Path>>(path: P, options: ReadOptions) -> read::SchemaIter<Self> {
+        // Safety: the caller upholds the requirements of `map_path`.
+        match unsafe { mmap::map_path(path) } {
+            Ok(bytes) => Self::read(bytes, options),
+            Err(error) => read::SchemaIter::Failed(Some(error)),
+        }
+    }
+
+    /// Read the rows whose value for the given column is equal to the value, skipping row groups whose bloom filter
+    /// for the column does not contain it.
+    ///
+    /// Values are compared by their plain encoding, and a row matches if any of its values for a repeated column do.
+    /// Note that this will override any reader properties set on the options builder.
+    fn read_matching<R: ChunkReader + 'static, V: bloom::BloomValue + ?Sized>(
+        reader: R,
+        options: ReadOptionsBuilder,
+        column: &ColumnInfo,
+        value: &V,
+    ) -> read::SchemaIter<Self> {
+        let options = options
+            .with_reader_properties(
+                ReaderProperties::builder()
+                    .set_read_bloom_filter(true)
+                    .build(),
+            )
+            .build();
+
+        match SerializedFileReader::new_with_options(reader, options)
+            .map_err(Error::from)
+            .and_then(|file_reader| {
+                let indices = bloom::matching_row_groups(&file_reader, column, value)?;
+                let file_reader = bloom::RowGroupSubsetReader::new(file_reader, indices);
+                let matches = bloom::matching_rows(&file_reader, column, value)?;
+
+                Ok((file_reader, matches))
+            }) {
+            Ok((file_reader, matches)) => read::SchemaIter::Filtered {
+                rows: RowIter::from_file_into(Box::new(file_reader)),
+                matches: matches.into_iter(),
+                _item: PhantomData,
+            },
+            Err(error) => read::SchemaIter::Failed(Some(error)),
+        }
+    }
+
+    /// Read an encrypted file by first decrypting it into an unencrypted in-memory copy.
+    ///
+    /// Row group predicates on the options see the row groups of the copy, which contain the same rows as the
+    /// non-empty row groups of the original file. See `encryption::decrypt` for the limitations of this approach.
+    #[cfg(feature = "encryption")]
+    fn read_encrypted<R: ChunkReader + 'static>(
+        reader: R,
+        options: ReadOptionsBuilder,
+        keys: &encryption::EncryptionKeys,
+    ) -> read::SchemaIter<Self> {
+        match encryption::decrypt(reader, keys) {
+            Ok(bytes) => Self::read(bytes, options.build()),
+            Err(error) => read::SchemaIter::Failed(Some(error)),
+        }
+    }
+
+    /// Read the file footer's key-value metadata without reading any rows.
+    fn read_metadata<R: ChunkReader + 'static>(
+        reader: R,
+    ) -> Result<metadata::SchemaMetadata, Error> {
+        let file_reader = SerializedFileReader::new(reader)?;
+
+        Ok(metadata::SchemaMetadata::from_metadata(
+            file_reader.metadata(),
+        ))
+    }
+
+    /// Read the file, failing immediately if the footer metadata does not indicate that it was written with this
+    /// schema.
+    fn read_checked<R: ChunkReader + 'static>(
+        reader: R,
+        options: ReadOptions,
+    ) -> read::SchemaIter<Self> {
+        match SerializedFileReader::new_with_options(reader, options)
+            .map_err(Error::from)
+            .and_then(|file_reader| {
+                metadata::SchemaMetadata::from_metadata(file_reader.metadata()).check::<Self>()?;
+
+                Ok(file_reader)
+            }) {
+            Ok(file_reader) => read::SchemaIter::Streaming {
+                rows: RowIter::from_file_into(Box::new(file_reader)),
+                _item: PhantomData,
+            },
+            Err(error) => read::SchemaIter::Failed(Some(error)),
+        }
+    }
+
     fn writer<W: std::io::Write + Send>(
         writer: W,
         properties: parquet::file::properties::WriterProperties,
     ) -> Result<Self::Writer<W>, Error>;
 
+    /// Create a writer that will encrypt the footer and columns with the given keys.
+    #[cfg(feature = "encryption")]
+    fn encrypted_writer<W: std::io::Write + Send>(
+        writer: W,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        keys: &encryption::EncryptionKeys,
+    ) -> Result<Self::Writer<W>, Error> {
+        Self::writer(
+            writer,
+            properties
+                .with_file_encryption_properties(keys.encryption_properties()?)
+                .build(),
+        )
+    }
+
+    /// Create a writer that will include the configured key-value metadata in the file footer.
+    ///
+    /// Note that this replaces any key-value metadata already set on the properties builder.
+    fn writer_with_metadata<W: std::io::Write + Send>(
+        writer: W,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        metadata: &metadata::MetadataOptions,
+    ) -> Result<Self::Writer<W>, Error> {
+        Self::writer(
+            writer,
+            properties
+                .set_key_value_metadata(Some(metadata.key_value_metadata::<Self>()))
+                .build(),
+        )
+    }
+
     fn write_row_groups<W: std::io::Write + Send, I: IntoIterator<Item = Vec<Self>>>(
         writer: W,
         properties: parquet::file::properties::WriterProperties,
@@ -171,4 +302,67 @@ pub trait Schema: Sized {
 
         writer.finish().map_err(E::from)
     }
+
+    /// Write values that are expected to be sorted by the given key.
+    ///
+    /// The key is recorded as the `sorting_columns` of each row group, and writing fails with
+    /// `Error::UnsortedValue` if a value's sort key value is less than the previous one.
+    fn write_sorted<
+        W: std::io::Write + Send,
+        E: From<Error>,
+        I: Iterator<Item = Result<Self, E>>,
+        S: Copy + std::ops::Add<Output = S> + PartialOrd,
+        F: Fn(&Self) -> S,
+    >(
+        writer: W,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        sort_key: &sort::SortKey<Self::SortColumn>,
+        max_size: S,
+        get_size: F,
+        fail_on_oversized: bool,
+        items: I,
+    ) -> Result<ParquetMetaData, E>
+    where
+        Self::SortColumn: sort::SortColumn + Copy,
+    {
+        let properties = properties
+            .set_sorting_columns(Some(sort_key.into()))
+            .build();
+
+        Self::write(
+            writer,
+            properties,
+            max_size,
+            get_size,
+            fail_on_oversized,
+            write::SortChecked::new(items, sort_key),
+        )
+    }
+
+    /// Write row groups of values that are expected to be sorted by the given key (see `write_sorted`).
+    fn write_row_groups_sorted<W: std::io::Write + Send, I: IntoIterator<Item = Vec<Self>>>(
+        writer: W,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        sort_key: &sort::SortKey<Self::SortColumn>,
+        groups: I,
+    ) -> Result<ParquetMetaData, Error>
+    where
+        Self::SortColumn: sort::SortColumn + Copy,
+    {
+        let properties = properties
+            .set_sorting_columns(Some(sort_key.into()))
+            .build();
+
+        let mut writer = Self::writer(writer, properties)?;
+        let mut sort_checker = write::SortChecker::new(sort_key);
+
+        for group in groups {
+            for value in &group {
+                sort_checker.check(value)?;
+            }
+
+            writer.write_row_group::<Error, _>(&mut group.iter().map(Ok))?;
+        }
+        writer.finish()
+    }
 }
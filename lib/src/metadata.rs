@@ -0,0 +1,148 @@
+use crate::{Schema, error::Error};
+use parquet::file::metadata::{KeyValue, ParquetMetaData};
+use std::collections::BTreeMap;
+
+pub const SOURCE_KEY: &str = "parquetry.schema.source";
+pub const HASH_KEY: &str = "parquetry.schema.hash";
+pub const VERSION_KEY: &str = "parquetry.schema.version";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A stable (FNV-1a) hash of the schema source, formatted as 16 hexadecimal digits.
+#[must_use]
+pub fn source_hash(source: &str) -> String {
+    let hash = source
+        .as_bytes()
+        .iter()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+        });
+
+    format!("{hash:016x}")
+}
+
+/// Configures the key-value metadata that will be written to the file footer.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MetadataOptions {
+    pub include_source: bool,
+    pub include_hash: bool,
+    pub version: Option<String>,
+    pub key_value: Vec<(String, String)>,
+}
+
+impl MetadataOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub const fn with_source(mut self) -> Self {
+        self.include_source = true;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_hash(mut self) -> Self {
+        self.include_hash = true;
+        self
+    }
+
+    #[must_use]
+    pub fn with_version<V: Into<String>>(mut self, version: V) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_key_value<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.key_value.push((key.into(), value.into()));
+        self
+    }
+
+    #[must_use]
+    pub fn key_value_metadata<S: Schema>(&self) -> Vec<KeyValue> {
+        let mut key_value_metadata = vec![];
+
+        if self.include_source {
+            key_value_metadata.push(KeyValue::new(
+                SOURCE_KEY.to_string(),
+                S::source().to_string(),
+            ));
+        }
+
+        if self.include_hash {
+            key_value_metadata.push(KeyValue::new(
+                HASH_KEY.to_string(),
+                source_hash(S::source()),
+            ));
+        }
+
+        if let Some(version) = &self.version {
+            key_value_metadata.push(KeyValue::new(VERSION_KEY.to_string(), version.clone()));
+        }
+
+        for (key, value) in &self.key_value {
+            key_value_metadata.push(KeyValue::new(key.clone(), value.clone()));
+        }
+
+        key_value_metadata
+    }
+}
+
+/// The key-value metadata read from a file footer.
+///
+/// Entries written by this library are parsed into their own fields, and all other entries are available in
+/// `key_value`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SchemaMetadata {
+    pub source: Option<String>,
+    pub hash: Option<String>,
+    pub version: Option<String>,
+    pub key_value: BTreeMap<String, Option<String>>,
+}
+
+impl SchemaMetadata {
+    #[must_use]
+    pub fn from_metadata(metadata: &ParquetMetaData) -> Self {
+        let mut result = Self::default();
+
+        for entry in metadata
+            .file_metadata()
+            .key_value_metadata()
+            .into_iter()
+            .flatten()
+        {
+            match entry.key.as_str() {
+                SOURCE_KEY => result.source.clone_from(&entry.value),
+                HASH_KEY => result.hash.clone_from(&entry.value),
+                VERSION_KEY => result.version.clone_from(&entry.value),
+                _ => {
+                    result
+                        .key_value
+                        .insert(entry.key.clone(), entry.value.clone());
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Confirm that the file was written with the schema `S`.
+    ///
+    /// If the file does not include a hash, the source is hashed instead, and if neither is present, this check fails.
+    pub fn check<S: Schema>(&self) -> Result<(), Error> {
+        let expected = source_hash(S::source());
+        let found = self
+            .hash
+            .clone()
+            .or_else(|| self.source.as_deref().map(source_hash));
+
+        if found.as_ref() == Some(&expected) {
+            Ok(())
+        } else {
+            Err(Error::SchemaMismatch { expected, found })
+        }
+    }
+}
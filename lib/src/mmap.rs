@@ -0,0 +1,22 @@
+#![allow(unsafe_code)]
+use crate::error::Error;
+use bytes::Bytes;
+use memmap2::Mmap;
+use std::{fs::File, path::Path};
+
+/// Memory-map the file at the given path as a read-only buffer.
+///
+/// The returned bytes keep the mapping alive.
+///
+/// # Safety
+///
+/// The caller must ensure that the file is not modified or truncated (by this or any other process) while the
+/// returned bytes or any buffer sliced from them are alive, since this is undefined behavior for a memory map.
+pub unsafe fn map_path<P: AsRef<Path>>(path: P) -> Result<Bytes, Error> {
+    let file = File::open(path)?;
+
+    // Safety: the mapping is read-only, and the caller guarantees that the file is not modified while it is in use.
+    let mmap = unsafe { Mmap::map(&file)? };
+
+    Ok(Bytes::from_owner(mmap))
+}
@@ -8,6 +8,12 @@ pub enum SchemaIter<T> {
         rows: RowIter<'static>,
         _item: PhantomData<T>,
     },
+    /// Only rows for which the corresponding flag is set are returned.
+    Filtered {
+        rows: RowIter<'static>,
+        matches: std::vec::IntoIter<bool>,
+        _item: PhantomData<T>,
+    },
 }
 
 impl<T: TryFrom<Row, Error = Error>> Iterator for SchemaIter<T> {
@@ -19,6 +25,10 @@ impl<T: TryFrom<Row, Error = Error>> Iterator for SchemaIter<T> {
             Self::Streaming { rows, .. } => rows
                 .next()
                 .map(|row| row.map_err(Error::from).and_then(T::try_from)),
+            Self::Filtered { rows, matches, .. } => rows
+                .zip(matches)
+                .find_map(|(row, is_match)| (is_match || row.is_err()).then_some(row))
+                .map(|row| row.map_err(Error::from).and_then(T::try_from)),
         }
     }
 }
@@ -1,12 +1,41 @@
+//! Sort keys and their byte encoding.
+//!
+//! Sort key values are encoded so that comparing the encoded bytes lexicographically gives the same order as comparing
+//! the values. This encoding is what `parquetry-sort` stores as keys in its sort databases and run files.
+//!
+//! # Migration
+//!
+//! Earlier versions wrote signed integers, floating point numbers, dates, and timestamps as their plain big-endian
+//! bytes, which does not order negative values correctly. These values are now written with the sign bit flipped (and
+//! all bits inverted for negative floating point values). Unsigned integers, booleans, strings, and byte arrays are
+//! encoded as before.
+//!
+//! Keys stored by earlier versions are not compatible with keys computed by this version, and there is no way to
+//! detect this from the stored data. Any sort database (with either backend) that was written by an earlier version and
+//! whose sort key includes a signed integer, floating point, date, or timestamp column must be rebuilt by inserting
+//! its values again (for example by iterating over it with the earlier version and writing a Parquet file).
+use crate::error::SortKeyError;
+use chrono::{DateTime, NaiveDate, Utc};
 use parquet::file::metadata::SortingColumn;
+use std::sync::Arc;
+
+const EPOCH_DATE: NaiveDate = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
 
 /// Characterizes a column type where each column has a unique (generally sequential) numeric index.
 pub trait SortColumn {
     fn index(&self) -> usize;
+
+    /// Whether the column is a derived expression over a repeated column (such as the first element of a list).
+    ///
+    /// Derived columns have the index of the underlying leaf column, but cannot be represented as Parquet
+    /// `sorting_columns`, so a key's sorting columns only include the columns preceding the first derived column.
+    fn is_derived(&self) -> bool {
+        false
+    }
 }
 
 /// Represents an ordering based on the value of a column.
-#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Sort<C> {
     pub column: C,
     pub descending: bool,
@@ -22,6 +51,7 @@ impl<C: Copy> Sort<C> {
         }
     }
 
+    #[must_use]
     pub const fn descending(&self) -> Self {
         Self {
             column: self.column,
@@ -30,6 +60,7 @@ impl<C: Copy> Sort<C> {
         }
     }
 
+    #[must_use]
     pub const fn nulls_first(&self) -> Self {
         Self {
             column: self.column,
@@ -38,12 +69,15 @@ impl<C: Copy> Sort<C> {
         }
     }
 
+    /// # Panics
+    ///
+    /// If the column index does not fit in an `i32` (which is not possible for a column in a Parquet file).
     pub fn sorting_column(&self) -> SortingColumn
     where
         C: SortColumn,
     {
         SortingColumn {
-            column_idx: self.column.index() as i32,
+            column_idx: i32::try_from(self.column.index()).expect("Column index out of range"),
             descending: self.descending,
             nulls_first: self.nulls_first,
         }
@@ -51,33 +85,289 @@ impl<C: Copy> Sort<C> {
 }
 
 /// Represents an ordering based on the values of a sequence of columns.
-#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
-pub enum SortKey<C> {
-    Columns1(Sort<C>),
-    Columns2(Sort<C>, Sort<C>),
-    Columns3(Sort<C>, Sort<C>, Sort<C>),
-    Columns4(Sort<C>, Sort<C>, Sort<C>, Sort<C>),
-    Columns5(Sort<C>, Sort<C>, Sort<C>, Sort<C>, Sort<C>),
+///
+/// Any non-zero number of columns is supported. The columns are shared, so cloning a key is cheap.
+///
+/// Keys were previously represented by the fixed-length `Columns1` through `Columns5` variants. A key should now be
+/// constructed with `Schema::sort_key` (or `SortKey::new` for unvalidated keys), and `columns` returns a slice.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SortKey<C> {
+    columns: Arc<[Sort<C>]>,
 }
 
 impl<C: Copy> SortKey<C> {
-    pub fn columns(&self) -> Vec<Sort<C>> {
+    /// Create a sort key without checking the columns against a schema.
+    ///
+    /// Returns `None` if there are no columns.
+    #[must_use]
+    pub fn new(columns: &[Sort<C>]) -> Option<Self> {
+        if columns.is_empty() {
+            None
+        } else {
+            Some(Self {
+                columns: columns.into(),
+            })
+        }
+    }
+
+    #[must_use]
+    pub fn columns(&self) -> &[Sort<C>] {
+        &self.columns
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Always returns false, since a key has at least one column.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+}
+
+impl<C> SortKey<C> {
+    /// Start building a key prefix from typed column values.
+    #[must_use]
+    pub const fn prefix(&self) -> KeyPrefix<'_, C> {
+        KeyPrefix {
+            sort_key: self,
+            bytes: vec![],
+            len: 0,
+        }
+    }
+}
+
+/// The encoded values of a prefix of the columns of a sort key.
+///
+/// Values must be provided in key order, and optional columns must be given `Option` values.
+#[derive(Clone, Debug)]
+pub struct KeyPrefix<'a, C> {
+    sort_key: &'a SortKey<C>,
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl<C> KeyPrefix<'_, C> {
+    pub fn push<V: SortValue + ?Sized>(mut self, value: &V) -> Result<Self, SortKeyError> {
+        let sort = self
+            .sort_key
+            .columns
+            .get(self.len)
+            .ok_or(SortKeyError::PrefixTooLong(self.len + 1))?;
+
+        value.write_sort_bytes(sort.descending, sort.nulls_first, &mut self.bytes);
+        self.len += 1;
+
+        Ok(self)
+    }
+
+    #[must_use]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// A Rust value that can be encoded as a component of a sort key.
+///
+/// The encoding is the same as the one used by generated `sort_key_value` implementations, and the encoded bytes have
+/// the same lexicographic order as the values (including negative numbers, dates, and timestamps).
+pub trait SortValue {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>);
+}
+
+impl<T: SortValue + ?Sized> SortValue for &T {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>) {
+        (*self).write_sort_bytes(descending, nulls_first, bytes);
+    }
+}
+
+impl<T: SortValue> SortValue for Option<T> {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>) {
         match self {
-            Self::Columns1(column_0) => vec![*column_0],
-            Self::Columns2(column_0, column_1) => vec![*column_0, *column_1],
-            Self::Columns3(column_0, column_1, column_2) => vec![*column_0, *column_1, *column_2],
-            Self::Columns4(column_0, column_1, column_2, column_3) => {
-                vec![*column_0, *column_1, *column_2, *column_3]
+            Some(value) => {
+                bytes.push(u8::from(nulls_first));
+                value.write_sort_bytes(descending, nulls_first, bytes);
+            }
+            None => bytes.push(u8::from(!nulls_first)),
+        }
+    }
+}
+
+impl SortValue for bool {
+    fn write_sort_bytes(&self, descending: bool, _nulls_first: bool, bytes: &mut Vec<u8>) {
+        bytes.push(u8::from(*self != descending));
+    }
+}
+
+macro_rules! unsigned_sort_value {
+    ($rust_type:ty) => {
+        impl SortValue for $rust_type {
+            fn write_sort_bytes(&self, descending: bool, _nulls_first: bool, bytes: &mut Vec<u8>) {
+                write_bytes(&self.to_be_bytes(), descending, bytes);
+            }
+        }
+    };
+}
+
+/// Signed integers are written with the sign bit flipped, so that negative values sort before positive ones.
+macro_rules! signed_sort_value {
+    ($rust_type:ty) => {
+        impl SortValue for $rust_type {
+            fn write_sort_bytes(&self, descending: bool, _nulls_first: bool, bytes: &mut Vec<u8>) {
+                write_bytes(&(self ^ <$rust_type>::MIN).to_be_bytes(), descending, bytes);
             }
-            Self::Columns5(column_0, column_1, column_2, column_3, column_4) => {
-                vec![*column_0, *column_1, *column_2, *column_3, *column_4]
+        }
+    };
+}
+
+/// Floating point values are written in the IEEE 754 total order (as for `total_cmp`): all bits are flipped for
+/// negative values and only the sign bit is flipped for positive values.
+macro_rules! float_sort_value {
+    ($rust_type:ty, $bits_type:ty) => {
+        impl SortValue for $rust_type {
+            fn write_sort_bytes(&self, descending: bool, _nulls_first: bool, bytes: &mut Vec<u8>) {
+                let bits = self.to_bits();
+                let sign = 1 << (<$bits_type>::BITS - 1);
+                let bits = if bits & sign == 0 { bits | sign } else { !bits };
+
+                write_bytes(&bits.to_be_bytes(), descending, bytes);
             }
         }
+    };
+}
+
+signed_sort_value!(i32);
+signed_sort_value!(i64);
+unsigned_sort_value!(u32);
+unsigned_sort_value!(u64);
+float_sort_value!(f32, u32);
+float_sort_value!(f64, u64);
+
+impl SortValue for NaiveDate {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>) {
+        // All dates supported by chrono are within the range of `i32` days from the epoch.
+        let days =
+            i32::try_from(self.signed_duration_since(EPOCH_DATE).num_days()).unwrap_or_default();
+
+        days.write_sort_bytes(descending, nulls_first, bytes);
+    }
+}
+
+impl SortValue for DateTime<Utc> {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>) {
+        self.timestamp_micros()
+            .write_sort_bytes(descending, nulls_first, bytes);
+    }
+}
+
+impl SortValue for str {
+    fn write_sort_bytes(&self, descending: bool, _nulls_first: bool, bytes: &mut Vec<u8>) {
+        write_escaped_bytes(self.as_bytes(), descending, bytes);
+    }
+}
+
+impl SortValue for String {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>) {
+        self.as_str()
+            .write_sort_bytes(descending, nulls_first, bytes);
+    }
+}
+
+impl SortValue for [u8] {
+    fn write_sort_bytes(&self, descending: bool, _nulls_first: bool, bytes: &mut Vec<u8>) {
+        write_escaped_bytes(self, descending, bytes);
+    }
+}
+
+impl SortValue for Vec<u8> {
+    fn write_sort_bytes(&self, descending: bool, nulls_first: bool, bytes: &mut Vec<u8>) {
+        self.as_slice()
+            .write_sort_bytes(descending, nulls_first, bytes);
+    }
+}
+
+/// Fixed-length byte arrays are written without escaping.
+impl<const N: usize> SortValue for [u8; N] {
+    fn write_sort_bytes(&self, descending: bool, _nulls_first: bool, bytes: &mut Vec<u8>) {
+        write_bytes(self, descending, bytes);
+    }
+}
+
+/// The smallest value according to the ascending sort key encoding (used by generated `min` expressions).
+pub fn min_by_sort_bytes<'a, T: SortValue + ?Sized + 'a, I: IntoIterator<Item = &'a T>>(
+    values: I,
+) -> Option<&'a T> {
+    values
+        .into_iter()
+        .map(|value| (sort_bytes(value), value))
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, value)| value)
+}
+
+/// The largest value according to the ascending sort key encoding (used by generated `max` expressions).
+pub fn max_by_sort_bytes<'a, T: SortValue + ?Sized + 'a, I: IntoIterator<Item = &'a T>>(
+    values: I,
+) -> Option<&'a T> {
+    values
+        .into_iter()
+        .map(|value| (sort_bytes(value), value))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, value)| value)
+}
+
+fn sort_bytes<T: SortValue + ?Sized>(value: &T) -> Vec<u8> {
+    let mut bytes = vec![];
+    value.write_sort_bytes(false, false, &mut bytes);
+    bytes
+}
+
+/// Write bytes without escaping (inverted for descending sorts), as for fixed-length values.
+pub fn write_bytes(value: &[u8], descending: bool, bytes: &mut Vec<u8>) {
+    for b in value {
+        bytes.push(if descending { !b } else { *b });
+    }
+}
+
+impl<C: Copy + SortColumn> From<&SortKey<C>> for Vec<SortingColumn> {
+    fn from(value: &SortKey<C>) -> Self {
+        value
+            .columns()
+            .iter()
+            .take_while(|sort| !sort.column.is_derived())
+            .map(Sort::sorting_column)
+            .collect()
     }
 }
 
 impl<C: Copy + SortColumn> From<SortKey<C>> for Vec<SortingColumn> {
     fn from(value: SortKey<C>) -> Self {
-        value.columns().iter().map(Sort::sorting_column).collect()
+        Self::from(&value)
     }
 }
+
+/// Write an escaped, self-delimiting encoding of a byte sequence for use in a sort key.
+///
+/// Each `0x00` is written as `0x00 0xFF`, and the value is terminated by `0x00 0x00`. This preserves lexicographic
+/// ordering and ensures that no encoded value is a prefix of another, so byte array columns can appear at any position
+/// in a compound key. All bytes (including the terminator) are inverted for descending sorts.
+pub fn write_escaped_bytes(value: &[u8], descending: bool, bytes: &mut Vec<u8>) {
+    let mask = if descending { u8::MAX } else { 0 };
+
+    for b in value {
+        bytes.push(b ^ mask);
+
+        if *b == 0 {
+            bytes.push(u8::MAX ^ mask);
+        }
+    }
+
+    bytes.push(mask);
+    bytes.push(mask);
+}
@@ -0,0 +1,75 @@
+use crate::{Schema, error::Error};
+use parquet::file::{
+    reader::{ChunkReader, FileReader, RowGroupReader},
+    serialized_reader::{ReadOptions, SerializedFileReader},
+};
+
+/// A schema with generated borrowed view types.
+///
+/// Views are produced from a batch of decoded column buffers (one row group at a time), and string and byte array
+/// fields borrow from these buffers, so reading a view does not allocate per value (except for lists).
+pub trait SchemaView: Schema {
+    type Ref<'a>
+    where
+        Self: 'a;
+    type Batch: Default;
+    type RefIter<'a>: Iterator<Item = Result<Self::Ref<'a>, Error>>
+    where
+        Self: 'a;
+
+    /// Decode all columns of the row group into the batch, returning the number of rows.
+    fn read_batch(
+        row_group_reader: &dyn RowGroupReader,
+        batch: &mut Self::Batch,
+    ) -> Result<usize, Error>;
+
+    fn batch_refs(batch: &Self::Batch) -> Self::RefIter<'_>;
+}
+
+/// Reads a file one row group at a time, reusing the same column buffers for each row group.
+pub struct BatchReader<S: SchemaView, R: ChunkReader> {
+    reader: SerializedFileReader<R>,
+    batch: S::Batch,
+    next_row_group_index: usize,
+}
+
+impl<S: SchemaView, R: ChunkReader + 'static> BatchReader<S, R> {
+    pub fn new(reader: R, options: ReadOptions) -> Result<Self, Error> {
+        Ok(Self {
+            reader: SerializedFileReader::new_with_options(reader, options)?,
+            batch: S::Batch::default(),
+            next_row_group_index: 0,
+        })
+    }
+
+    /// Decode the next row group and return an iterator of views into it.
+    pub fn next_batch(&mut self) -> Result<Option<S::RefIter<'_>>, Error> {
+        if self.next_row_group_index < self.reader.num_row_groups() {
+            {
+                let row_group_reader = self.reader.get_row_group(self.next_row_group_index)?;
+                S::read_batch(row_group_reader.as_ref(), &mut self.batch)?;
+            }
+
+            self.next_row_group_index += 1;
+
+            Ok(Some(S::batch_refs(&self.batch)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Used by generated code to look up a definition or repetition level.
+pub fn level(levels: &[i16], position: usize, column_index: usize) -> Result<i16, Error> {
+    levels
+        .get(position)
+        .copied()
+        .ok_or(Error::InvalidColumnData { column_index })
+}
+
+/// Used by generated code to look up a decoded value.
+pub fn value<T>(values: &[T], position: usize, column_index: usize) -> Result<&T, Error> {
+    values
+        .get(position)
+        .ok_or(Error::InvalidColumnData { column_index })
+}
@@ -1,4 +1,4 @@
-use crate::error::Error;
+use crate::{Schema, error::Error, sort::SortKey};
 use std::{iter::Peekable, marker::PhantomData};
 
 pub struct WriteConfig<T, S, F: Fn(&T) -> S> {
@@ -164,3 +164,72 @@ impl<
         }
     }
 }
+
+/// Checks that values are non-decreasing according to a sort key.
+pub struct SortChecker<'a, T: Schema> {
+    sort_key: &'a SortKey<T::SortColumn>,
+    previous: Option<Vec<u8>>,
+    row_index: usize,
+}
+
+impl<'a, T: Schema> SortChecker<'a, T> {
+    #[must_use]
+    pub const fn new(sort_key: &'a SortKey<T::SortColumn>) -> Self {
+        Self {
+            sort_key,
+            previous: None,
+            row_index: 0,
+        }
+    }
+
+    /// Check the next value, failing with the value's position if it is less than the previous value.
+    pub fn check(&mut self, value: &T) -> Result<(), Error> {
+        let key = value.sort_key_value(self.sort_key);
+
+        if self
+            .previous
+            .as_ref()
+            .is_some_and(|previous| key < *previous)
+        {
+            return Err(Error::UnsortedValue {
+                row_index: self.row_index,
+            });
+        }
+
+        self.previous = Some(key);
+        self.row_index += 1;
+
+        Ok(())
+    }
+}
+
+/// Wraps an iterator of values, failing if a value is out of sort order.
+pub(super) struct SortChecked<'a, T: Schema, I> {
+    underlying: I,
+    checker: SortChecker<'a, T>,
+}
+
+impl<'a, T: Schema, I> SortChecked<'a, T, I> {
+    pub(super) const fn new(underlying: I, sort_key: &'a SortKey<T::SortColumn>) -> Self {
+        Self {
+            underlying,
+            checker: SortChecker::new(sort_key),
+        }
+    }
+}
+
+impl<T: Schema, E: From<Error>, I: Iterator<Item = Result<T, E>>> Iterator
+    for SortChecked<'_, T, I>
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.underlying.next().map(|result| {
+            result.and_then(|value| {
+                self.checker.check(&value)?;
+
+                Ok(value)
+            })
+        })
+    }
+}
@@ -0,0 +1,51 @@
+use crate::Error;
+
+#[cfg(feature = "rocksdb")]
+mod rocksdb;
+#[cfg(feature = "sled")]
+mod sled;
+
+#[cfg(feature = "rocksdb")]
+pub use self::rocksdb::RocksDbBackend;
+#[cfg(feature = "sled")]
+pub use self::sled::SledBackend;
+
+/// The backend used by `SortDb` when none is specified (RocksDB if it is enabled).
+#[cfg(feature = "rocksdb")]
+pub type DefaultBackend = RocksDbBackend;
+#[cfg(all(feature = "sled", not(feature = "rocksdb")))]
+pub type DefaultBackend = SledBackend;
+
+/// An ordered key-value store that can be used as storage for a `SortDb`.
+///
+/// Values are sequences of length-prefixed records, and the backend is responsible for combining the values for a
+/// key (usually according to a `MergePolicy`) when a value is appended to an existing one.
+pub trait Backend {
+    type Bytes: AsRef<[u8]>;
+    type Entries<'a>: Iterator<Item = Result<(Self::Bytes, Self::Bytes), Error>>
+    where
+        Self: 'a;
+
+    /// Store the value for the key, combining it with any existing value.
+    fn append(&self, key: &[u8], value: &[u8]) -> Result<(), Error>;
+
+    /// Store a batch of values in order, combining each with any existing value for its key.
+    ///
+    /// Backends without a write-ahead log ignore `write_ahead_log`.
+    fn append_batch(
+        &self,
+        entries: &[(Vec<u8>, Vec<u8>)],
+        _write_ahead_log: bool,
+    ) -> Result<(), Error> {
+        for (key, value) in entries {
+            self.append(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Iterate over entries in key order, from an inclusive lower bound to an exclusive upper bound.
+    fn entries(&self, lower: Option<&[u8]>, upper: Option<&[u8]>) -> Self::Entries<'_>;
+
+    fn flush(&self) -> Result<(), Error>;
+}
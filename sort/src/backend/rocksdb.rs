@@ -0,0 +1,125 @@
+use super::Backend;
+use crate::{Error, policy::MergePolicy};
+use ::rocksdb::{
+    DB, DBIterator, IteratorMode, MergeOperands, Options, ReadOptions, WriteBatch, WriteOptions,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::path::Path;
+use std::sync::Arc;
+
+type Entry = (Box<[u8]>, Box<[u8]>);
+
+/// A RocksDB database where values are combined by a merge operator.
+#[derive(Clone)]
+pub struct RocksDbBackend {
+    db: Arc<DB>,
+}
+
+impl RocksDbBackend {
+    /// Open a database that keeps all values for each key.
+    pub fn open<P: AsRef<Path>>(path: P, mut options: Options) -> Result<Self, Error> {
+        options.set_merge_operator_associative("concatenation", concatenation_merge);
+
+        Ok(Self {
+            db: Arc::new(DB::open(&options, path)?),
+        })
+    }
+
+    /// Open a database that combines values according to the given policy.
+    ///
+    /// The policy is applied by the RocksDB merge operator, so only the combined values are stored. A database should
+    /// always be opened with the same policy.
+    pub fn open_with_policy<A: Serialize + DeserializeOwned + 'static, P: AsRef<Path>>(
+        path: P,
+        mut options: Options,
+        policy: MergePolicy<A>,
+    ) -> Result<Self, Error> {
+        options.set_merge_operator_associative(
+            policy.name(),
+            move |_new_key: &[u8], existing_value: Option<&[u8]>, operands: &MergeOperands| {
+                policy
+                    .merge_encoded(existing_value.into_iter().chain(operands))
+                    .ok()
+            },
+        );
+
+        Ok(Self {
+            db: Arc::new(DB::open(&options, path)?),
+        })
+    }
+
+    #[must_use]
+    pub fn db(&self) -> &DB {
+        &self.db
+    }
+}
+
+impl Backend for RocksDbBackend {
+    type Bytes = Box<[u8]>;
+    type Entries<'a> =
+        std::iter::Map<DBIterator<'a>, fn(Result<Entry, ::rocksdb::Error>) -> Result<Entry, Error>>;
+
+    fn append(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        Ok(self.db.merge(key, value)?)
+    }
+
+    fn append_batch(
+        &self,
+        entries: &[(Vec<u8>, Vec<u8>)],
+        write_ahead_log: bool,
+    ) -> Result<(), Error> {
+        let mut batch = WriteBatch::default();
+
+        for (key, value) in entries {
+            batch.merge(key, value);
+        }
+
+        let mut write_options = WriteOptions::default();
+        write_options.disable_wal(!write_ahead_log);
+
+        Ok(self.db.write_opt(batch, &write_options)?)
+    }
+
+    fn entries(&self, lower: Option<&[u8]>, upper: Option<&[u8]>) -> Self::Entries<'_> {
+        let mut read_options = ReadOptions::default();
+
+        if let Some(lower) = lower {
+            read_options.set_iterate_lower_bound(lower);
+        }
+
+        if let Some(upper) = upper {
+            read_options.set_iterate_upper_bound(upper);
+        }
+
+        let map_entry: fn(Result<Entry, ::rocksdb::Error>) -> Result<Entry, Error> =
+            |result| result.map_err(Error::from);
+
+        self.db
+            .iterator_opt(IteratorMode::Start, read_options)
+            .map(map_entry)
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        Ok(self.db.flush()?)
+    }
+}
+
+fn concatenation_merge(
+    _new_key: &[u8],
+    existing_value: Option<&[u8]>,
+    operands: &MergeOperands,
+) -> Option<Vec<u8>> {
+    let capacity =
+        existing_value.map_or(0, <[u8]>::len) + operands.iter().map(<[u8]>::len).sum::<usize>();
+    let mut result: Vec<u8> = Vec::with_capacity(capacity);
+
+    if let Some(value) = existing_value {
+        result.extend(value);
+    }
+
+    for operand in operands {
+        result.extend(operand);
+    }
+
+    Some(result)
+}
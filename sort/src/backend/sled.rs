@@ -0,0 +1,90 @@
+use super::Backend;
+use crate::{Error, policy::MergePolicy};
+use ::sled::{IVec, Iter, Tree};
+use serde::{Serialize, de::DeserializeOwned};
+use std::ops::Bound;
+use std::path::Path;
+
+type Entry = (IVec, IVec);
+
+/// A sled tree (a pure-Rust embedded database) where values are combined by a merge operator.
+#[derive(Clone)]
+pub struct SledBackend {
+    tree: Tree,
+}
+
+impl SledBackend {
+    /// Open a database that keeps all values for each key.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_policy::<(), _>(path, MergePolicy::KeepAll)
+    }
+
+    /// Open a database that combines values according to the given policy.
+    pub fn open_with_policy<A: Serialize + DeserializeOwned + 'static, P: AsRef<Path>>(
+        path: P,
+        policy: MergePolicy<A>,
+    ) -> Result<Self, Error> {
+        let db = ::sled::open(path)?;
+
+        Ok(Self::from_tree(Tree::clone(&db), policy))
+    }
+
+    /// Use an existing tree, which must not be used for anything else.
+    pub fn from_tree<A: Serialize + DeserializeOwned + 'static>(
+        tree: Tree,
+        policy: MergePolicy<A>,
+    ) -> Self {
+        tree.set_merge_operator(
+            move |_key: &[u8], existing_value: Option<&[u8]>, value: &[u8]| {
+                policy
+                    .merge_encoded(existing_value.into_iter().chain(std::iter::once(value)))
+                    .ok()
+            },
+        );
+
+        Self { tree }
+    }
+
+    #[must_use]
+    pub const fn tree(&self) -> &Tree {
+        &self.tree
+    }
+}
+
+impl Backend for SledBackend {
+    type Bytes = IVec;
+    type Entries<'a> = std::iter::Map<
+        std::iter::Flatten<std::option::IntoIter<Iter>>,
+        fn(Result<Entry, ::sled::Error>) -> Result<Entry, Error>,
+    >;
+
+    fn append(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.tree.merge(key, value)?;
+
+        Ok(())
+    }
+
+    fn entries(&self, lower: Option<&[u8]>, upper: Option<&[u8]>) -> Self::Entries<'_> {
+        let map_entry: fn(Result<Entry, ::sled::Error>) -> Result<Entry, Error> =
+            |result| result.map_err(Error::from);
+
+        let is_empty = lower
+            .zip(upper)
+            .is_some_and(|(lower, upper)| lower >= upper);
+
+        let entries = (!is_empty).then(|| {
+            self.tree.range::<&[u8], _>((
+                lower.map_or(Bound::Unbounded, Bound::Included),
+                upper.map_or(Bound::Unbounded, Bound::Excluded),
+            ))
+        });
+
+        entries.into_iter().flatten().map(map_entry)
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        self.tree.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,464 @@
+use crate::{
+    Error,
+    backend::{Backend, DefaultBackend},
+    encoding,
+};
+#[cfg(feature = "rocksdb")]
+use crate::{backend::RocksDbBackend, policy::MergePolicy};
+use parquetry::{
+    Schema,
+    sort::{SortColumn, SortKey},
+    write::{SchemaWrite, SizeChecked, SizeCounter},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fs::File;
+use std::ops::Bound;
+use std::path::Path;
+
+const DEFAULT_BULK_BATCH_SIZE: usize = 10_000;
+
+/// Sorts values by storing them in an ordered key-value store (RocksDB by default).
+#[derive(Clone)]
+pub struct SortDb<A: Schema + DeserializeOwned + Serialize, B: Backend = DefaultBackend> {
+    backend: B,
+    sort_key: SortKey<A::SortColumn>,
+}
+
+#[cfg(feature = "rocksdb")]
+impl<A: Schema + DeserializeOwned + Serialize> SortDb<A, RocksDbBackend> {
+    pub fn open<P: AsRef<Path>>(path: P, sort_key: SortKey<A::SortColumn>) -> Result<Self, Error> {
+        let mut options = rocksdb::Options::default();
+        options.create_if_missing(true);
+
+        Self::open_opt(path, sort_key, options)
+    }
+
+    pub fn open_opt<P: AsRef<Path>>(
+        path: P,
+        sort_key: SortKey<A::SortColumn>,
+        options: rocksdb::Options,
+    ) -> Result<Self, Error> {
+        Ok(Self::new(RocksDbBackend::open(path, options)?, sort_key))
+    }
+
+    /// Open a database that combines values with the same sort key according to the given policy.
+    ///
+    /// The policy is applied by the RocksDB merge operator, so only the combined values are stored. A database should
+    /// always be opened with the same policy.
+    pub fn open_with_policy<P: AsRef<Path>>(
+        path: P,
+        sort_key: SortKey<A::SortColumn>,
+        policy: MergePolicy<A>,
+    ) -> Result<Self, Error>
+    where
+        A: 'static,
+    {
+        let mut options = rocksdb::Options::default();
+        options.create_if_missing(true);
+
+        Self::open_opt_with_policy(path, sort_key, policy, options)
+    }
+
+    pub fn open_opt_with_policy<P: AsRef<Path>>(
+        path: P,
+        sort_key: SortKey<A::SortColumn>,
+        policy: MergePolicy<A>,
+        options: rocksdb::Options,
+    ) -> Result<Self, Error>
+    where
+        A: 'static,
+    {
+        Ok(Self::new(
+            RocksDbBackend::open_with_policy(path, options, policy)?,
+            sort_key,
+        ))
+    }
+}
+
+impl<A: Schema + DeserializeOwned + Serialize, B: Backend> SortDb<A, B> {
+    pub const fn new(backend: B, sort_key: SortKey<A::SortColumn>) -> Self {
+        Self { backend, sort_key }
+    }
+
+    pub const fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn insert(&self, value: &A) -> Result<(), Error>
+    where
+        A::SortColumn: Copy,
+    {
+        let (key, bytes) = self.encode_entry(value)?;
+
+        self.backend.append(&key, &bytes)
+    }
+
+    /// Insert values in batches, encoding each batch on a separate thread.
+    ///
+    /// Batches are written in order, so values with equal sort keys are stored in iteration order. The progress
+    /// function is called with the total number of values inserted after each batch is written.
+    pub fn insert_bulk<I: IntoIterator<Item = A>, P: FnMut(usize)>(
+        &self,
+        values: I,
+        options: &BulkOptions,
+        mut progress: P,
+    ) -> Result<usize, Error>
+    where
+        A: Sync,
+        A::SortColumn: Copy + Send + Sync,
+        B: Sync,
+    {
+        let batch_size = options.batch_size.max(1);
+        let mut values = values.into_iter();
+        let mut count = 0;
+
+        loop {
+            let chunk = values
+                .by_ref()
+                .take(batch_size * options.threads.max(1))
+                .collect::<Vec<_>>();
+
+            if chunk.is_empty() {
+                break;
+            }
+
+            let batches = std::thread::scope(|scope| {
+                let handles = chunk
+                    .chunks(batch_size)
+                    .map(|batch| scope.spawn(|| self.encode_batch(batch)))
+                    .collect::<Vec<_>>();
+
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })?;
+
+            for batch in batches {
+                self.backend.append_batch(&batch, options.write_ahead_log)?;
+                count += batch.len();
+                progress(count);
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Insert values from a parallel iterator in batches.
+    ///
+    /// Batches are encoded and written concurrently, so the order of values with equal sort keys is not preserved.
+    /// The progress function is called with the total number of values inserted after each batch is written.
+    #[cfg(feature = "rayon")]
+    pub fn insert_par_iter<I: rayon::iter::IntoParallelIterator<Item = A>, P: Fn(usize) + Sync>(
+        &self,
+        values: I,
+        options: &BulkOptions,
+        progress: P,
+    ) -> Result<usize, Error>
+    where
+        A: Send,
+        A::SortColumn: Copy + Send + Sync,
+        B: Sync,
+    {
+        use rayon::iter::ParallelIterator;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let batch_size = options.batch_size.max(1);
+        let count = AtomicUsize::new(0);
+
+        let write_batch = |batch: &[(Vec<u8>, Vec<u8>)]| -> Result<(), Error> {
+            self.backend.append_batch(batch, options.write_ahead_log)?;
+            progress(count.fetch_add(batch.len(), Ordering::Relaxed) + batch.len());
+
+            Ok(())
+        };
+
+        values
+            .into_par_iter()
+            .map(|value| self.encode_entry(&value))
+            .try_fold(Vec::new, |mut batch, entry| {
+                batch.push(entry?);
+
+                if batch.len() >= batch_size {
+                    write_batch(&batch)?;
+                    batch.clear();
+                }
+
+                Ok::<_, Error>(batch)
+            })
+            .try_for_each(|batch| {
+                let batch = batch?;
+
+                if batch.is_empty() {
+                    Ok(())
+                } else {
+                    write_batch(&batch)
+                }
+            })?;
+
+        Ok(count.into_inner())
+    }
+
+    pub fn flush(&self) -> Result<(), Error> {
+        self.backend.flush()
+    }
+
+    fn encode_entry(&self, value: &A) -> Result<(Vec<u8>, Vec<u8>), Error>
+    where
+        A::SortColumn: Copy,
+    {
+        let key = value.sort_key_value(&self.sort_key);
+        let value_bytes = encoding::encode_value(value)?;
+
+        let mut bytes = Vec::with_capacity(value_bytes.len() + 4);
+        encoding::push_length_prefixed(&mut bytes, &value_bytes);
+
+        Ok((key, bytes))
+    }
+
+    fn encode_batch(&self, values: &[A]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Error>
+    where
+        A::SortColumn: Copy,
+    {
+        values
+            .iter()
+            .map(|value| self.encode_entry(value))
+            .collect()
+    }
+
+    /// Iterate over all stored values in key order.
+    pub fn iter(&self) -> SortDbIter<'_, A, B> {
+        SortDbIter::new(self.backend.entries(None, None))
+    }
+
+    /// Iterate over stored values with keys in the given range, in key order.
+    ///
+    /// The bounds are key prefixes (see `parquetry::sort::KeyPrefix`): an included upper bound includes all keys that
+    /// start with the prefix, and an excluded lower bound excludes them.
+    pub fn range(&self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> SortDbIter<'_, A, B> {
+        let lower_successor;
+        let upper_successor;
+
+        // If the lower bound can't be satisfied, the upper bound is set to make the range empty.
+        let (lower, empty_upper) = match lower {
+            Bound::Included(prefix) => (Some(prefix), None),
+            Bound::Excluded(prefix) => {
+                lower_successor = prefix_successor(prefix);
+
+                match &lower_successor {
+                    Some(successor) => (Some(successor.as_slice()), None),
+                    // No key can follow all of the keys starting with the prefix.
+                    None => (Some(prefix), Some(prefix)),
+                }
+            }
+            Bound::Unbounded => (None, None),
+        };
+
+        let upper = match upper {
+            Bound::Included(prefix) => {
+                upper_successor = prefix_successor(prefix);
+                empty_upper.or(upper_successor.as_deref())
+            }
+            Bound::Excluded(prefix) => empty_upper.or(Some(prefix)),
+            Bound::Unbounded => empty_upper,
+        };
+
+        SortDbIter::new(self.backend.entries(lower, upper))
+    }
+
+    /// Iterate over stored values with keys that start with the given prefix, in key order.
+    pub fn prefix(&self, prefix: &[u8]) -> SortDbIter<'_, A, B> {
+        self.range(Bound::Included(prefix), Bound::Included(prefix))
+    }
+
+    /// The number of stored values.
+    pub fn count(&self) -> Result<usize, Error> {
+        let mut count = 0;
+
+        for result in self.backend.entries(None, None) {
+            let (_, value_bytes) = result?;
+
+            for entry in encoding::LengthPrefixed::new(value_bytes.as_ref()) {
+                entry?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    pub fn write<
+        W: std::io::Write + Send,
+        S: Copy + std::ops::Add<Output = S> + PartialOrd,
+        F: Fn(&A) -> S,
+    >(
+        &self,
+        writer: W,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        max_size: S,
+        get_size: F,
+        fail_on_oversized: bool,
+    ) -> Result<parquet::file::metadata::ParquetMetaData, Error>
+    where
+        A::SortColumn: Copy + SortColumn,
+    {
+        let properties = properties
+            .set_sorting_columns(Some((&self.sort_key).into()))
+            .build();
+
+        let mut writer = A::writer(writer, properties)?;
+        let mut size_counter = SizeCounter::new(max_size, get_size);
+        let mut row_group_index = 0;
+
+        for item in self.iter() {
+            let item = item?;
+
+            loop {
+                if size_counter.add(&item) {
+                    match size_counter.checked(item) {
+                        SizeChecked::Valid(value) => writer.write_item(&value),
+                        SizeChecked::Oversized { value, .. } => {
+                            if fail_on_oversized {
+                                Err(parquetry::error::Error::OversizedRowValue { row_group_index })
+                            } else {
+                                writer.write_item(&value)
+                            }
+                        }
+                    }?;
+
+                    break;
+                } else {
+                    writer.finish_row_group()?;
+                    size_counter.reset();
+
+                    row_group_index += 1;
+                }
+            }
+        }
+
+        if !size_counter.is_empty() {
+            writer.finish_row_group()?;
+        }
+
+        Ok(writer.finish()?)
+    }
+
+    pub fn write_file<
+        P: AsRef<Path>,
+        S: Copy + std::ops::Add<Output = S> + PartialOrd,
+        F: Fn(&A) -> S,
+    >(
+        &self,
+        output: P,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        max_size: S,
+        get_size: F,
+        fail_on_oversized: bool,
+    ) -> Result<parquet::file::metadata::ParquetMetaData, Error>
+    where
+        A::SortColumn: Copy + SortColumn,
+    {
+        let file = File::create(output)?;
+
+        self.write(file, properties, max_size, get_size, fail_on_oversized)
+    }
+}
+
+/// Options for bulk insertion into a `SortDb`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BulkOptions {
+    /// The number of values written to the backend at once.
+    pub batch_size: usize,
+    /// The number of threads used to encode batches.
+    pub threads: usize,
+    /// Whether writes go through the write-ahead log (this can be disabled for throwaway databases).
+    pub write_ahead_log: bool,
+}
+
+impl BulkOptions {
+    #[must_use]
+    pub const fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    #[must_use]
+    pub const fn without_write_ahead_log(mut self) -> Self {
+        self.write_ahead_log = false;
+        self
+    }
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BULK_BATCH_SIZE,
+            threads: std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
+            write_ahead_log: true,
+        }
+    }
+}
+
+/// Iterates over decoded values in key order.
+pub struct SortDbIter<'a, A, B: Backend + 'a = DefaultBackend> {
+    entries: B::Entries<'a>,
+    values: std::vec::IntoIter<A>,
+}
+
+impl<'a, A, B: Backend + 'a> SortDbIter<'a, A, B> {
+    fn new(entries: B::Entries<'a>) -> Self {
+        Self {
+            entries,
+            values: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<'a, A: DeserializeOwned, B: Backend + 'a> Iterator for SortDbIter<'a, A, B> {
+    type Item = Result<A, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.values.next() {
+                return Some(Ok(value));
+            }
+
+            let values = self.entries.next()?.and_then(|(_, value_bytes)| {
+                encoding::LengthPrefixed::new(value_bytes.as_ref())
+                    .map(|entry| entry.and_then(encoding::decode_value::<A>))
+                    .collect::<Result<Vec<_>, _>>()
+            });
+
+            match values {
+                Ok(values) => {
+                    self.values = values.into_iter();
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+/// The smallest byte string greater than every string starting with the prefix, if there is one.
+fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut successor = prefix.to_vec();
+
+    while let Some(last) = successor.pop() {
+        if last < u8::MAX {
+            successor.push(last + 1);
+            return Some(successor);
+        }
+    }
+
+    None
+}
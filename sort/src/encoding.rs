@@ -0,0 +1,102 @@
+use crate::Error;
+use bincode::serde::Compat;
+use serde::{Serialize, de::DeserializeOwned};
+
+pub fn encode_value<A: Serialize>(value: &A) -> Result<Vec<u8>, Error> {
+    Ok(bincode::encode_to_vec(
+        Compat(value),
+        bincode::config::standard(),
+    )?)
+}
+
+pub fn decode_value<A: DeserializeOwned>(bytes: &[u8]) -> Result<A, Error> {
+    let (Compat(value), _) =
+        bincode::decode_from_slice::<Compat<A>, _>(bytes, bincode::config::standard())?;
+
+    Ok(value)
+}
+
+/// Append a big-endian `u32` length followed by the given bytes.
+pub fn push_length_prefixed(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.extend((value.len() as u32).to_be_bytes());
+    bytes.extend(value);
+}
+
+/// Iterates over the entries in a concatenation of length-prefixed byte sequences.
+///
+/// Trailing bytes that do not make up a complete entry result in an error.
+pub struct LengthPrefixed<'a> {
+    bytes: &'a [u8],
+    current: usize,
+}
+
+impl<'a> LengthPrefixed<'a> {
+    #[must_use]
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, current: 0 }
+    }
+}
+
+impl<'a> Iterator for LengthPrefixed<'a> {
+    type Item = Result<&'a [u8], Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == self.bytes.len() {
+            None
+        } else if self.current + 4 <= self.bytes.len() {
+            let len =
+                u32::from_be_bytes(self.bytes[self.current..self.current + 4].try_into().ok()?)
+                    as usize;
+
+            self.current += 4;
+
+            if self.current + len > self.bytes.len() {
+                self.current = self.bytes.len();
+
+                Some(Err(Error::InvalidValue(self.bytes.to_vec())))
+            } else {
+                let value = &self.bytes[self.current..self.current + len];
+                self.current += len;
+
+                Some(Ok(value))
+            }
+        } else {
+            self.current = self.bytes.len();
+
+            Some(Err(Error::InvalidValue(self.bytes.to_vec())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LengthPrefixed, push_length_prefixed};
+
+    #[test]
+    fn length_prefixed_truncated() {
+        let mut bytes = vec![];
+        push_length_prefixed(&mut bytes, b"foo");
+        push_length_prefixed(&mut bytes, b"");
+
+        let entries = LengthPrefixed::new(&bytes).collect::<Result<Vec<_>, _>>();
+        assert_eq!(entries.ok(), Some(vec![&b"foo"[..], &b""[..]]));
+
+        for len in 1..bytes.len() {
+            let mut entries = LengthPrefixed::new(&bytes[..len]);
+
+            // The first entry is complete after seven bytes.
+            if len >= 7 {
+                assert_eq!(entries.next().and_then(Result::ok), Some(&b"foo"[..]));
+            }
+
+            if len != 7 {
+                assert!(
+                    entries.next().is_some_and(|entry| entry.is_err()),
+                    "truncated at {len}"
+                );
+            }
+
+            assert!(entries.next().is_none());
+        }
+    }
+}
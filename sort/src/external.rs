@@ -0,0 +1,399 @@
+use crate::{Error, encoding};
+use parquetry::{
+    Schema,
+    sort::{SortColumn, SortKey},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+const DEFAULT_MEMORY_BUDGET: usize = 64 * 1024 * 1024;
+
+type Record = (Vec<u8>, Vec<u8>);
+
+/// A pure-Rust external merge sorter with the same interface as `SortDb`.
+///
+/// Values are buffered in memory until the encoded size of the buffer exceeds the memory budget, at which point the
+/// buffer is sorted and spilled to a temporary file. Writing merges the spilled runs and the current buffer. Values
+/// with equal sort keys are written in insertion order.
+pub struct ExternalSorter<A: Schema> {
+    sort_key: SortKey<A::SortColumn>,
+    records: RecordSorter,
+}
+
+impl<A: Schema + DeserializeOwned + Serialize> ExternalSorter<A> {
+    pub fn new(sort_key: SortKey<A::SortColumn>) -> Self {
+        Self {
+            sort_key,
+            records: RecordSorter::new(),
+        }
+    }
+
+    /// Set the approximate number of bytes of encoded keys and values to buffer before spilling to disk.
+    #[must_use]
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.records = self.records.with_memory_budget(memory_budget);
+        self
+    }
+
+    /// Set the directory for spilled runs (the system temporary directory is used by default).
+    #[must_use]
+    pub fn with_temp_dir<P: AsRef<Path>>(mut self, temp_dir: P) -> Self {
+        self.records = self.records.with_temp_dir(temp_dir);
+        self
+    }
+
+    /// The number of sorted runs that have been spilled to disk.
+    pub fn run_count(&self) -> usize {
+        self.records.run_count()
+    }
+
+    pub fn insert(&self, value: &A) -> Result<(), Error>
+    where
+        A::SortColumn: Copy,
+    {
+        let key = value.sort_key_value(&self.sort_key);
+        let value_bytes = encoding::encode_value(value)?;
+
+        self.records.insert(key, value_bytes)
+    }
+
+    pub fn write<
+        W: std::io::Write + Send,
+        S: Copy + std::ops::Add<Output = S> + PartialOrd,
+        F: Fn(&A) -> S,
+    >(
+        &self,
+        writer: W,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        max_size: S,
+        get_size: F,
+        fail_on_oversized: bool,
+    ) -> Result<parquet::file::metadata::ParquetMetaData, Error>
+    where
+        A::SortColumn: Copy + SortColumn,
+    {
+        let properties = properties
+            .set_sorting_columns(Some((&self.sort_key).into()))
+            .build();
+
+        self.records.merge(|records| {
+            A::write(
+                writer,
+                properties,
+                max_size,
+                get_size,
+                fail_on_oversized,
+                records.map(|result| result.and_then(|(_, value)| encoding::decode_value(&value))),
+            )
+        })?
+    }
+
+    pub fn write_file<
+        P: AsRef<Path>,
+        S: Copy + std::ops::Add<Output = S> + PartialOrd,
+        F: Fn(&A) -> S,
+    >(
+        &self,
+        output: P,
+        properties: parquet::file::properties::WriterPropertiesBuilder,
+        max_size: S,
+        get_size: F,
+        fail_on_oversized: bool,
+    ) -> Result<parquet::file::metadata::ParquetMetaData, Error>
+    where
+        A::SortColumn: Copy + SortColumn,
+    {
+        let file = File::create(output)?;
+
+        self.write(file, properties, max_size, get_size, fail_on_oversized)
+    }
+}
+
+/// An external merge sorter for encoded keys and values.
+///
+/// This is the storage used by `ExternalSorter`, for callers that compute their own sort keys and value encodings
+/// (for example for schemas that are only known at runtime). Keys are compared as bytes.
+pub struct RecordSorter {
+    memory_budget: usize,
+    temp_dir: Option<PathBuf>,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    buffer: Vec<Record>,
+    buffer_size: usize,
+    runs: Vec<File>,
+}
+
+impl RecordSorter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            temp_dir: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Set the approximate number of bytes of keys and values to buffer before spilling to disk.
+    #[must_use]
+    pub const fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Set the directory for spilled runs (the system temporary directory is used by default).
+    #[must_use]
+    pub fn with_temp_dir<P: AsRef<Path>>(mut self, temp_dir: P) -> Self {
+        self.temp_dir = Some(temp_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// The number of sorted runs that have been spilled to disk.
+    pub fn run_count(&self) -> usize {
+        self.state().runs.len()
+    }
+
+    pub fn insert(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), Error> {
+        let mut state = self.state();
+        state.buffer_size += key.len() + value.len();
+        state.buffer.push((key, value));
+
+        if state.buffer_size >= self.memory_budget {
+            self.spill(&mut state)?;
+        }
+
+        Ok(())
+    }
+
+    /// Merge the spilled runs and the current buffer, and pass the merged records to the given function.
+    ///
+    /// Records are in key order, and records with equal keys are in insertion order.
+    pub fn merge<R, F: FnOnce(MergedRecords<'_>) -> R>(&self, f: F) -> Result<R, Error> {
+        let mut state = self.state();
+        state.buffer.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut runs = Vec::with_capacity(state.runs.len() + 1);
+
+        for file in &state.runs {
+            let mut file = file.try_clone()?;
+            file.seek(SeekFrom::Start(0))?;
+            runs.push(Run::File(BufReader::new(file)));
+        }
+
+        runs.push(Run::Memory(state.buffer.iter()));
+
+        Ok(f(MergedRecords::new(runs)?))
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn spill(&self, state: &mut State) -> Result<(), Error> {
+        // The sort is stable, so values with equal keys stay in insertion order within a run.
+        state.buffer.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let file = match &self.temp_dir {
+            Some(temp_dir) => tempfile::tempfile_in(temp_dir)?,
+            None => tempfile::tempfile()?,
+        };
+
+        let mut writer = BufWriter::new(file);
+        let mut bytes = vec![];
+
+        for (key, value) in state.buffer.drain(..) {
+            bytes.clear();
+            encoding::push_length_prefixed(&mut bytes, &key);
+            encoding::push_length_prefixed(&mut bytes, &value);
+            writer.write_all(&bytes)?;
+        }
+
+        let file = writer.into_inner().map_err(|error| error.into_error())?;
+
+        state.runs.push(file);
+        state.buffer_size = 0;
+
+        Ok(())
+    }
+}
+
+impl Default for RecordSorter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum Run<'a> {
+    File(BufReader<File>),
+    Memory(std::slice::Iter<'a, Record>),
+}
+
+impl<'a> Run<'a> {
+    fn next_record(&mut self) -> Result<Option<(Cow<'a, [u8]>, Cow<'a, [u8]>)>, Error> {
+        match self {
+            Self::File(reader) => match read_length(reader)? {
+                Some(key_len) => {
+                    let key = read_bytes(reader, key_len)?;
+                    let value_len =
+                        read_length(reader)?.ok_or_else(|| Error::InvalidValue(key.clone()))?;
+                    let value = read_bytes(reader, value_len)?;
+
+                    Ok(Some((Cow::Owned(key), Cow::Owned(value))))
+                }
+                None => Ok(None),
+            },
+            Self::Memory(records) => Ok(records.next().map(|(key, value)| {
+                (
+                    Cow::Borrowed(key.as_slice()),
+                    Cow::Borrowed(value.as_slice()),
+                )
+            })),
+        }
+    }
+}
+
+/// Read a length prefix, returning `None` only if the reader is at the end of the run.
+///
+/// A partial length prefix means that the run file has been truncated, and is an error.
+fn read_length<R: Read>(reader: &mut R) -> Result<Option<usize>, Error> {
+    let mut buffer = [0; 4];
+    let mut read = 0;
+
+    while read < buffer.len() {
+        match reader.read(&mut buffer[read..]) {
+            Ok(0) => break,
+            Ok(count) => read += count,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    match read {
+        0 => Ok(None),
+        4 => Ok(Some(u32::from_be_bytes(buffer) as usize)),
+        _ => Err(std::io::Error::from(ErrorKind::UnexpectedEof).into()),
+    }
+}
+
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> Result<Vec<u8>, Error> {
+    let mut buffer = vec![0; len];
+    reader.read_exact(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+/// A k-way merge of sorted runs, where ties are broken by run index.
+pub struct MergedRecords<'a> {
+    runs: Vec<Run<'a>>,
+    heap: BinaryHeap<Reverse<(Cow<'a, [u8]>, usize)>>,
+    values: Vec<Option<Cow<'a, [u8]>>>,
+}
+
+impl<'a> MergedRecords<'a> {
+    fn new(runs: Vec<Run<'a>>) -> Result<Self, Error> {
+        let mut merge = Self {
+            heap: BinaryHeap::with_capacity(runs.len()),
+            values: vec![None; runs.len()],
+            runs,
+        };
+
+        for index in 0..merge.runs.len() {
+            merge.advance(index)?;
+        }
+
+        Ok(merge)
+    }
+
+    fn advance(&mut self, index: usize) -> Result<(), Error> {
+        if let Some((key, value)) = self.runs[index].next_record()? {
+            self.heap.push(Reverse((key, index)));
+            self.values[index] = Some(value);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for MergedRecords<'a> {
+    type Item = Result<(Cow<'a, [u8]>, Cow<'a, [u8]>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((key, index)) = self.heap.pop()?;
+        let value = self.values[index].take()?;
+
+        Some(self.advance(index).map(|()| (key, value)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Run, encoding};
+    use std::io::{BufReader, Seek, SeekFrom, Write};
+
+    fn run_file(bytes: &[u8]) -> Result<Run<'static>, Box<dyn std::error::Error>> {
+        let mut file = tempfile::tempfile()?;
+        file.write_all(bytes)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        Ok(Run::File(BufReader::new(file)))
+    }
+
+    fn record(key: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        encoding::push_length_prefixed(&mut bytes, key);
+        encoding::push_length_prefixed(&mut bytes, value);
+        bytes
+    }
+
+    #[test]
+    fn run_file_complete() -> Result<(), Box<dyn std::error::Error>> {
+        let mut bytes = record(b"a", b"foo");
+        bytes.extend(record(b"b", b""));
+
+        let mut run = run_file(&bytes)?;
+
+        let (key, value) = run.next_record()?.ok_or("missing record")?;
+        assert_eq!((key.as_ref(), value.as_ref()), (&b"a"[..], &b"foo"[..]));
+
+        let (key, value) = run.next_record()?.ok_or("missing record")?;
+        assert_eq!((key.as_ref(), value.as_ref()), (&b"b"[..], &b""[..]));
+
+        assert!(run.next_record()?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_file_truncated() -> Result<(), Box<dyn std::error::Error>> {
+        let mut bytes = record(b"a", b"foo");
+        bytes.extend(record(b"b", b"bar"));
+
+        // Every truncation point except the record boundaries must result in an error after the first record.
+        for len in 1..bytes.len() {
+            let mut run = run_file(&bytes[..len])?;
+            let boundary = record(b"a", b"foo").len();
+
+            if len < boundary {
+                assert!(run.next_record().is_err(), "truncated at {len}");
+            } else {
+                assert!(run.next_record()?.is_some());
+
+                if len == boundary {
+                    assert!(run.next_record()?.is_none());
+                } else {
+                    assert!(run.next_record().is_err(), "truncated at {len}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
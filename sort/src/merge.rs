@@ -0,0 +1,172 @@
+use crate::Error;
+use parquet::{
+    file::{
+        metadata::{ParquetMetaData, SortingColumn},
+        properties::WriterPropertiesBuilder,
+        reader::{ChunkReader, FileReader},
+        serialized_reader::SerializedFileReader,
+    },
+    record::{Row, reader::RowIter},
+};
+use parquetry::{
+    Schema,
+    read::SchemaIter,
+    sort::{SortColumn, SortKey},
+};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Merge inputs that are each sorted by the given key into a single sorted output.
+///
+/// Every row group in every input must have `sorting_columns` metadata matching the key. Values are streamed from the
+/// inputs, and values with equal keys are written in input order.
+pub fn merge_sorted<
+    A: Schema + TryFrom<Row, Error = parquetry::error::Error>,
+    R: ChunkReader + 'static,
+    I: IntoIterator<Item = R>,
+    W: std::io::Write + Send,
+    S: Copy + std::ops::Add<Output = S> + PartialOrd,
+    F: Fn(&A) -> S,
+>(
+    inputs: I,
+    sort_key: &SortKey<A::SortColumn>,
+    writer: W,
+    properties: WriterPropertiesBuilder,
+    max_size: S,
+    get_size: F,
+    fail_on_oversized: bool,
+) -> Result<ParquetMetaData, Error>
+where
+    A::SortColumn: Copy + SortColumn,
+{
+    let sorting_columns: Vec<SortingColumn> = sort_key.into();
+    let mut sources = vec![];
+
+    for (input_index, input) in inputs.into_iter().enumerate() {
+        let file_reader = SerializedFileReader::new(input)?;
+
+        for (row_group_index, row_group) in file_reader.metadata().row_groups().iter().enumerate() {
+            if row_group.sorting_columns() != Some(&sorting_columns) {
+                return Err(Error::SortingColumnsMismatch {
+                    input_index,
+                    row_group_index,
+                });
+            }
+        }
+
+        sources.push(SchemaIter::<A>::Streaming {
+            rows: RowIter::from_file_into(Box::new(file_reader)),
+            _item: PhantomData,
+        });
+    }
+
+    let merge = SortedMerge::new(sources, sort_key)?;
+
+    A::write(
+        writer,
+        properties
+            .set_sorting_columns(Some(sorting_columns))
+            .build(),
+        max_size,
+        get_size,
+        fail_on_oversized,
+        merge,
+    )
+}
+
+pub fn merge_sorted_files<
+    A: Schema + TryFrom<Row, Error = parquetry::error::Error>,
+    P: AsRef<Path>,
+    O: AsRef<Path>,
+    S: Copy + std::ops::Add<Output = S> + PartialOrd,
+    F: Fn(&A) -> S,
+>(
+    inputs: &[P],
+    sort_key: &SortKey<A::SortColumn>,
+    output: O,
+    properties: WriterPropertiesBuilder,
+    max_size: S,
+    get_size: F,
+    fail_on_oversized: bool,
+) -> Result<ParquetMetaData, Error>
+where
+    A::SortColumn: Copy + SortColumn,
+{
+    let files = inputs
+        .iter()
+        .map(File::open)
+        .collect::<Result<Vec<_>, _>>()?;
+    let output = File::create(output)?;
+
+    merge_sorted(
+        files,
+        sort_key,
+        output,
+        properties,
+        max_size,
+        get_size,
+        fail_on_oversized,
+    )
+}
+
+/// A k-way merge of typed sorted inputs, where ties are broken by input index.
+struct SortedMerge<'a, A: Schema, I> {
+    sort_key: &'a SortKey<A::SortColumn>,
+    inputs: Vec<I>,
+    heap: BinaryHeap<Reverse<(Vec<u8>, usize)>>,
+    values: Vec<Option<A>>,
+    row_counts: Vec<usize>,
+}
+
+impl<'a, A: Schema, I: Iterator<Item = Result<A, parquetry::error::Error>>> SortedMerge<'a, A, I> {
+    fn new(inputs: Vec<I>, sort_key: &'a SortKey<A::SortColumn>) -> Result<Self, Error> {
+        let mut merge = Self {
+            sort_key,
+            heap: BinaryHeap::with_capacity(inputs.len()),
+            values: inputs.iter().map(|_| None).collect(),
+            row_counts: vec![0; inputs.len()],
+            inputs,
+        };
+
+        for input_index in 0..merge.inputs.len() {
+            merge.advance(input_index, None)?;
+        }
+
+        Ok(merge)
+    }
+
+    fn advance(&mut self, input_index: usize, previous_key: Option<&[u8]>) -> Result<(), Error> {
+        if let Some(value) = self.inputs[input_index].next().transpose()? {
+            let key = value.sort_key_value(self.sort_key);
+
+            if previous_key.is_some_and(|previous_key| key.as_slice() < previous_key) {
+                return Err(Error::UnsortedInput {
+                    input_index,
+                    row_index: self.row_counts[input_index],
+                });
+            }
+
+            self.heap.push(Reverse((key, input_index)));
+            self.values[input_index] = Some(value);
+            self.row_counts[input_index] += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl<A: Schema, I: Iterator<Item = Result<A, parquetry::error::Error>>> Iterator
+    for SortedMerge<'_, A, I>
+{
+    type Item = Result<A, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((key, input_index)) = self.heap.pop()?;
+        let value = self.values[input_index].take()?;
+
+        Some(self.advance(input_index, Some(&key)).map(|()| value))
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{Error, encoding};
+use serde::{Serialize, de::DeserializeOwned};
+use std::sync::Arc;
+
+/// Determines how values with the same sort key are combined.
+pub enum MergePolicy<A> {
+    /// Keep all values, in insertion order.
+    KeepAll,
+    /// Keep only the first value inserted for each key.
+    KeepFirst,
+    /// Keep only the last value inserted for each key.
+    KeepLast,
+    /// Combine values with the given function, which receives the earlier value first.
+    ///
+    /// Values may be combined in any grouping (but never reordered), so the function should be associative.
+    Merge(Arc<dyn Fn(A, A) -> A + Send + Sync>),
+}
+
+impl<A> MergePolicy<A> {
+    pub fn merge<F: Fn(A, A) -> A + Send + Sync + 'static>(f: F) -> Self {
+        Self::Merge(Arc::new(f))
+    }
+
+    /// The name used to identify the policy's merge operator.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::KeepAll => "concatenation",
+            Self::KeepFirst => "keep_first",
+            Self::KeepLast => "keep_last",
+            Self::Merge(_) => "merge",
+        }
+    }
+}
+
+impl<A: Serialize + DeserializeOwned> MergePolicy<A> {
+    /// Combine encoded values (each a sequence of length-prefixed records), given in insertion order.
+    pub fn merge_encoded<'a, I: IntoIterator<Item = &'a [u8]>>(
+        &self,
+        values: I,
+    ) -> Result<Vec<u8>, Error> {
+        let mut result = vec![];
+
+        match self {
+            Self::KeepAll => {
+                for value in values {
+                    result.extend(value);
+                }
+            }
+            Self::KeepFirst => {
+                if let Some(first) = values
+                    .into_iter()
+                    .flat_map(encoding::LengthPrefixed::new)
+                    .next()
+                {
+                    encoding::push_length_prefixed(&mut result, first?);
+                }
+            }
+            Self::KeepLast => {
+                let mut last = None;
+
+                for entry in values.into_iter().flat_map(encoding::LengthPrefixed::new) {
+                    last = Some(entry?);
+                }
+
+                if let Some(last) = last {
+                    encoding::push_length_prefixed(&mut result, last);
+                }
+            }
+            Self::Merge(f) => {
+                let mut merged = None;
+
+                for entry in values.into_iter().flat_map(encoding::LengthPrefixed::new) {
+                    let value = encoding::decode_value::<A>(entry?)?;
+
+                    merged = Some(match merged {
+                        Some(previous) => f(previous, value),
+                        None => value,
+                    });
+                }
+
+                if let Some(merged) = merged {
+                    encoding::push_length_prefixed(&mut result, &encoding::encode_value(&merged)?);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl<A> Clone for MergePolicy<A> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::KeepAll => Self::KeepAll,
+            Self::KeepFirst => Self::KeepFirst,
+            Self::KeepLast => Self::KeepLast,
+            Self::Merge(f) => Self::Merge(f.clone()),
+        }
+    }
+}
+
+impl<A> Default for MergePolicy<A> {
+    fn default() -> Self {
+        Self::KeepAll
+    }
+}
@@ -1,16 +1,21 @@
 #[allow(dead_code)]
-mod nested;
+pub mod nested;
 #[allow(dead_code)]
-mod simple;
+pub mod simple;
 #[allow(dead_code)]
-mod two_list_levels;
+pub mod two_list_levels;
 
 #[cfg(test)]
 mod test {
+    use super::nested::{self, Nested};
     use super::simple::{Simple, columns};
+    use super::two_list_levels::TwoListLevels;
     use chrono::{DateTime, Utc};
-    use parquet::file::properties::WriterProperties;
-    use parquetry::{Schema, sort::Sort};
+    use parquet::file::{properties::WriterProperties, serialized_reader::ReadOptionsBuilder};
+    use parquetry::{
+        Schema, encryption::EncryptionKeys, metadata::MetadataOptions, sort::Sort,
+        write::SchemaWrite,
+    };
     use std::cmp::{Ordering, Reverse};
 
     #[derive(Clone, Debug, Eq, PartialEq)]
@@ -40,7 +45,7 @@ mod test {
 
             let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Mno), Sort::new(columns::SortColumn::Abc)]).unwrap();
 
-            by_key_bytes.sort_by_key(|value| value.sort_key_value(sort_key));
+            by_key_bytes.sort_by_key(|value| value.sort_key_value(&sort_key));
             by_fields.sort_by_key(|value| (value.mno, value.abc));
 
             by_key_bytes == by_fields
@@ -53,7 +58,7 @@ mod test {
             let mut by_fields = values.clone();
 
             let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Abc), Sort::new(columns::SortColumn::Def)]).unwrap();
-            by_key_bytes.sort_by_key(|value| value.sort_key_value(sort_key));
+            by_key_bytes.sort_by_key(|value| value.sort_key_value(&sort_key));
             by_fields.sort_by_key(|value| (value.abc, NullLastOption(value.def.clone())));
 
             by_key_bytes == by_fields
@@ -66,7 +71,7 @@ mod test {
             let mut by_fields = values.clone();
 
             let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Def).nulls_first(), Sort::new(columns::SortColumn::Abc)]).unwrap();
-            by_key_bytes.sort_by_key(|value| value.sort_key_value(sort_key));
+            by_key_bytes.sort_by_key(|value| value.sort_key_value(&sort_key));
             by_fields.sort_by_key(|value| (value.def.clone(), value.abc));
 
             by_key_bytes == by_fields
@@ -79,13 +84,153 @@ mod test {
             let mut by_fields = values.clone();
 
             let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Def).nulls_first().descending(), Sort::new(columns::SortColumn::Abc).descending()]).unwrap();
-            by_key_bytes.sort_by_key(|value| value.sort_key_value(sort_key));
+            by_key_bytes.sort_by_key(|value| value.sort_key_value(&sort_key));
             by_fields.sort_by_key(|value| (value.def.clone().map(Reverse), Reverse(value.abc)));
 
             by_key_bytes == by_fields
         }
     }
 
+    quickcheck::quickcheck! {
+        fn sort_by_key_six_columns(values: Vec<Simple>) -> bool {
+            let mut by_key_bytes = values.clone();
+            let mut by_fields = values.clone();
+
+            let sort_key = Simple::sort_key(&[
+                Sort::new(columns::SortColumn::Mno),
+                Sort::new(columns::SortColumn::ReqDef),
+                Sort::new(columns::SortColumn::Def),
+                Sort::new(columns::SortColumn::Stu).descending(),
+                Sort::new(columns::SortColumn::Pqr),
+                Sort::new(columns::SortColumn::Abc),
+            ]).unwrap();
+            by_key_bytes.sort_by_key(|value| value.sort_key_value(&sort_key));
+            by_fields.sort_by_key(|value| {
+                (
+                    value.mno,
+                    value.req_def.clone(),
+                    NullLastOption(value.def.clone()),
+                    Reverse(value.stu),
+                    NullLastOption(value.pqr),
+                    value.abc,
+                )
+            });
+
+            sort_key.len() == 6 && by_key_bytes == by_fields
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn sort_by_key_byte_arrays(values: Vec<Simple>) -> bool {
+            let mut by_key_bytes = values.clone();
+            let mut by_fields = values.clone();
+
+            let sort_key = Simple::sort_key(&[
+                Sort::new(columns::SortColumn::Efgh),
+                Sort::new(columns::SortColumn::ReqDef).descending(),
+                Sort::new(columns::SortColumn::Abcd),
+                Sort::new(columns::SortColumn::Abc),
+            ]).unwrap();
+            by_key_bytes.sort_by_key(|value| value.sort_key_value(&sort_key));
+            by_fields.sort_by_key(|value| {
+                (
+                    NullLastOption(value.efgh.clone()),
+                    Reverse(value.req_def.clone()),
+                    value.abcd,
+                    value.abc,
+                )
+            });
+
+            by_key_bytes == by_fields
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn escaped_bytes_ordering(a: Vec<u8>, b: Vec<u8>, next_a: u8, next_b: u8, descending: bool) -> bool {
+            let encode = |value: &[u8], next: u8| {
+                let mut bytes = vec![];
+                parquetry::sort::write_escaped_bytes(value, descending, &mut bytes);
+                bytes.push(next);
+                bytes
+            };
+
+            let expected = (&a, next_a).cmp(&(&b, next_b));
+            let expected = if descending && a != b { expected.reverse() } else { expected };
+
+            encode(&a, next_a).cmp(&encode(&b, next_b)) == expected
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn numeric_sort_value_ordering(a: i64, b: i64, x: f64, y: f64, descending: bool) -> bool {
+            use parquetry::sort::SortValue;
+
+            let encode = |value: &dyn SortValue| {
+                let mut bytes = vec![];
+                value.write_sort_bytes(descending, false, &mut bytes);
+                bytes
+            };
+
+            let ordered = |ordering: Ordering| if descending { ordering.reverse() } else { ordering };
+            let timestamp = |micros: i64| DateTime::from_timestamp_micros(micros / 8).unwrap();
+
+            encode(&a).cmp(&encode(&b)) == ordered(a.cmp(&b))
+                && encode(&(a as i32)).cmp(&encode(&(b as i32))) == ordered((a as i32).cmp(&(b as i32)))
+                && encode(&timestamp(a)).cmp(&encode(&timestamp(b))) == ordered((a / 8).cmp(&(b / 8)))
+                && encode(&x).cmp(&encode(&y)) == ordered(x.total_cmp(&y))
+                && encode(&(x as f32)).cmp(&encode(&(y as f32))) == ordered((x as f32).total_cmp(&(y as f32)))
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn sort_by_key_derived_columns(values: Vec<Simple>) -> bool {
+            let mut by_key_bytes = values.clone();
+            let mut by_fields = values.clone();
+
+            let sort_key = Simple::sort_key(&[
+                Sort::new(columns::SortColumn::JklFirst),
+                Sort::new(columns::SortColumn::GhiLength),
+                Sort::new(columns::SortColumn::GhiMax).descending(),
+                Sort::new(columns::SortColumn::Abc),
+            ]).unwrap();
+            by_key_bytes.sort_by_key(|value| value.sort_key_value(&sort_key));
+            by_fields.sort_by_key(|value| {
+                (
+                    NullLastOption(value.jkl.as_ref().and_then(|jkl| jkl.first().copied())),
+                    value.ghi.len(),
+                    NullLastOption(value.ghi.iter().flatten().max().copied().map(Reverse)),
+                    value.abc,
+                )
+            });
+
+            let sorting_columns: Vec<parquet::file::metadata::SortingColumn> = (&Simple::sort_key(&[
+                Sort::new(columns::SortColumn::Abc),
+                Sort::new(columns::SortColumn::GhiFirst),
+                Sort::new(columns::SortColumn::Mno),
+            ]).unwrap()).into();
+
+            by_key_bytes == by_fields && sorting_columns.len() == 1
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn sort_db_by_derived_columns(values: Vec<Simple>) -> bool {
+            let test_db_dir = tempfile::Builder::new().prefix("Simple-derived-sort-db").tempdir().unwrap();
+
+            let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::GhiLength), Sort::new(columns::SortColumn::Abc)]).unwrap();
+            let sort_db = parquetry_sort::SortDb::open(test_db_dir.path(), sort_key).unwrap();
+
+            for value in &values {
+                sort_db.insert(value).unwrap();
+            }
+
+            let mut by_fields = values.clone();
+            by_fields.sort_by_key(|value| (value.ghi.len(), value.abc));
+
+            sort_db.iter().collect::<Result<Vec<_>, _>>().unwrap() == by_fields
+        }
+    }
+
     quickcheck::quickcheck! {
         fn sort_db_by_key_simple(values: Vec<Simple>) -> bool {
             let test_db_dir = tempfile::Builder::new().prefix("Simple-sort-db").tempdir().unwrap();
@@ -112,6 +257,305 @@ mod test {
         }
     }
 
+    quickcheck::quickcheck! {
+        fn sled_sort_db_by_key_simple(values: Vec<Simple>) -> bool {
+            let test_db_dir = tempfile::Builder::new().prefix("Simple-sled-sort-db").tempdir().unwrap();
+            let last_db_dir = tempfile::Builder::new().prefix("Simple-sled-last-sort-db").tempdir().unwrap();
+
+            let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Mno), Sort::new(columns::SortColumn::Abc)]).unwrap();
+            let backend = parquetry_sort::backend::SledBackend::open(test_db_dir.path()).unwrap();
+            let sort_db = parquetry_sort::SortDb::new(backend, sort_key.clone());
+
+            let last_backend = parquetry_sort::backend::SledBackend::open_with_policy(
+                last_db_dir.path(),
+                parquetry_sort::MergePolicy::<Simple>::KeepLast,
+            )
+            .unwrap();
+            let last_sort_db = parquetry_sort::SortDb::new(last_backend, sort_key);
+
+            for value in &values {
+                sort_db.insert(value).unwrap();
+                last_sort_db.insert(value).unwrap();
+            }
+
+            sort_db.flush().unwrap();
+
+            let mut by_fields = values.clone();
+            by_fields.sort_by_key(|value| (value.mno, value.abc));
+
+            let mut last_by_fields = std::collections::BTreeMap::new();
+
+            for value in &values {
+                last_by_fields.insert((value.mno, value.abc), value.clone());
+            }
+
+            sort_db.iter().collect::<Result<Vec<_>, _>>().unwrap() == by_fields
+                && sort_db.count().unwrap() == values.len()
+                && last_sort_db.iter().collect::<Result<Vec<_>, _>>().unwrap()
+                    == last_by_fields.into_values().collect::<Vec<_>>()
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn sort_db_insert_bulk(values: Vec<Simple>) -> bool {
+            let test_db_dir = tempfile::Builder::new().prefix("Simple-bulk-sort-db").tempdir().unwrap();
+            let par_db_dir = tempfile::Builder::new().prefix("Simple-par-bulk-sort-db").tempdir().unwrap();
+
+            let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Abc)]).unwrap();
+            let sort_db = parquetry_sort::SortDb::<Simple>::open(test_db_dir.path(), sort_key.clone()).unwrap();
+            let par_sort_db = parquetry_sort::SortDb::<Simple>::open(par_db_dir.path(), sort_key).unwrap();
+
+            let options = parquetry_sort::BulkOptions::default()
+                .with_batch_size(7)
+                .with_threads(3)
+                .without_write_ahead_log();
+
+            let mut progress = vec![];
+            let count = sort_db
+                .insert_bulk(values.clone(), &options, |count| progress.push(count))
+                .unwrap();
+
+            let par_progress = std::sync::Mutex::new(vec![]);
+            let par_count = par_sort_db
+                .insert_par_iter(values.clone(), &options, |count| par_progress.lock().unwrap().push(count))
+                .unwrap();
+
+            // The sort is stable for sequential bulk inserts.
+            let mut by_abc = values.clone();
+            by_abc.sort_by_key(|value| value.abc);
+
+            let mut par_values = par_sort_db.iter().collect::<Result<Vec<_>, _>>().unwrap();
+            par_values.sort_by(|a, b| a.abc.cmp(&b.abc).then_with(|| format!("{a:?}").cmp(&format!("{b:?}"))));
+
+            let mut expected_par_values = values.clone();
+            expected_par_values.sort_by(|a, b| a.abc.cmp(&b.abc).then_with(|| format!("{a:?}").cmp(&format!("{b:?}"))));
+
+            let mut par_progress = par_progress.into_inner().unwrap();
+            par_progress.sort_unstable();
+
+            count == values.len()
+                && par_count == values.len()
+                && progress.last().copied().unwrap_or(0) == values.len()
+                && progress.windows(2).all(|pair| pair[1] - pair[0] <= 7)
+                && par_progress.last().copied().unwrap_or(0) == values.len()
+                && sort_db.iter().collect::<Result<Vec<_>, _>>().unwrap() == by_abc
+                && par_values == expected_par_values
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn sort_db_merge_policies(values: Vec<Simple>) -> bool {
+            let mut by_abc = std::collections::BTreeMap::<u64, Vec<Simple>>::new();
+
+            for value in &values {
+                by_abc.entry(value.abc).or_default().push(value.clone());
+            }
+
+            let keep_first = by_abc.values().map(|group| group[0].clone()).collect::<Vec<_>>();
+            let keep_last = by_abc.values().map(|group| group[group.len() - 1].clone()).collect::<Vec<_>>();
+            let merged = by_abc
+                .values()
+                .map(|group| group.iter().rev().find(|value| value.mno).unwrap_or(&group[0]).clone())
+                .collect::<Vec<_>>();
+
+            sort_db_with_policy(&values, parquetry_sort::MergePolicy::KeepFirst).unwrap() == keep_first
+                && sort_db_with_policy(&values, parquetry_sort::MergePolicy::KeepLast).unwrap() == keep_last
+                && sort_db_with_policy(
+                    &values,
+                    parquetry_sort::MergePolicy::merge(|a: Simple, b: Simple| if b.mno { b } else { a }),
+                )
+                .unwrap()
+                    == merged
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn merge_sorted_files(groups: Vec<Vec<Simple>>) -> bool {
+            let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Mno), Sort::new(columns::SortColumn::Abc)]).unwrap();
+            let properties = WriterProperties::builder().set_sorting_columns(Some((&sort_key).into())).build();
+
+            let inputs = groups
+                .iter()
+                .map(|group| {
+                    let mut sorted = group.clone();
+                    sorted.sort_by_key(|value| value.sort_key_value(&sort_key));
+
+                    let mut buffer = vec![];
+                    Simple::write_row_groups(&mut buffer, properties.clone(), vec![sorted]).unwrap();
+                    bytes::Bytes::from(buffer)
+                })
+                .collect::<Vec<_>>();
+
+            let mut buffer = vec![];
+            parquetry_sort::merge_sorted(inputs, &sort_key, &mut buffer, WriterProperties::builder(), 100, |_: &Simple| 1, false).unwrap();
+
+            let read_values = Simple::read_bytes(buffer, ReadOptionsBuilder::new().build())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            let mut by_fields = groups.concat();
+            by_fields.sort_by_key(|value| (value.mno, value.abc));
+
+            read_values == by_fields
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn sort_db_iter_and_range(values: Vec<Simple>, abc: u64) -> bool {
+            let test_db_dir = tempfile::Builder::new().prefix("Simple-range-sort-db").tempdir().unwrap();
+
+            let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Mno), Sort::new(columns::SortColumn::Abc)]).unwrap();
+            let sort_db = parquetry_sort::SortDb::open(test_db_dir.path(), sort_key.clone()).unwrap();
+
+            for value in &values {
+                sort_db.insert(value).unwrap();
+            }
+
+            let mut by_fields = values.clone();
+            by_fields.sort_by_key(|value| (value.mno, value.abc));
+
+            let all = sort_db.iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+            let true_prefix = sort_key.prefix().push(&true).unwrap().into_bytes();
+            let with_true = sort_db.prefix(&true_prefix).collect::<Result<Vec<_>, _>>().unwrap();
+
+            let lower = sort_key.prefix().push(&false).unwrap().push(&abc).unwrap().into_bytes();
+            let from_abc = sort_db
+                .range(std::ops::Bound::Included(lower.as_slice()), std::ops::Bound::Excluded(true_prefix.as_slice()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            let prefixes_match = values.iter().all(|value| {
+                sort_key.prefix().push(&value.mno).unwrap().push(&value.abc).unwrap().bytes()
+                    == value.sort_key_value(&sort_key)
+            });
+
+            all == by_fields
+                && sort_db.count().unwrap() == values.len()
+                && with_true == by_fields.iter().filter(|value| value.mno).cloned().collect::<Vec<_>>()
+                && from_abc
+                    == by_fields
+                        .iter()
+                        .filter(|value| !value.mno && value.abc >= abc)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                && prefixes_match
+                && sort_key.prefix().push(&true).unwrap().push(&abc).unwrap().push(&abc).is_err()
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn write_sorted_checks_order(values: Vec<Simple>) -> bool {
+            let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Abc), Sort::new(columns::SortColumn::ReqDef)]).unwrap();
+
+            let mut sorted = values.clone();
+            sorted.sort_by_key(|value| value.sort_key_value(&sort_key));
+
+            let first_unsorted = values
+                .windows(2)
+                .position(|pair| pair[1].sort_key_value(&sort_key) < pair[0].sort_key_value(&sort_key))
+                .map(|index| index + 1);
+
+            let mut buffer = vec![];
+            let metadata = Simple::write_sorted::<_, parquetry::error::Error, _, _, _>(
+                &mut buffer,
+                WriterProperties::builder(),
+                &sort_key,
+                10,
+                |_| 1,
+                false,
+                sorted.iter().cloned().map(Ok),
+            )
+            .unwrap();
+
+            let expected_sorting_columns: Vec<_> = (&sort_key).into();
+            let unsorted_result = Simple::write_row_groups_sorted(
+                std::io::sink(),
+                WriterProperties::builder(),
+                &sort_key,
+                vec![values.clone()],
+            );
+
+            metadata.row_groups().iter().all(|row_group| row_group.sorting_columns() == Some(&expected_sorting_columns))
+                && match (first_unsorted, unsorted_result) {
+                    (None, Ok(_)) => true,
+                    (Some(index), Err(parquetry::error::Error::UnsortedValue { row_index })) => index == row_index,
+                    _ => false,
+                }
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn external_sort_by_key_simple(values: Vec<Simple>, memory_budget: u16) -> bool {
+            let test_parquet_dir = tempfile::Builder::new().prefix("Simple-external-sort-data").tempdir().unwrap();
+            let test_file_path = test_parquet_dir.path().join("external-sort-data.parquet");
+
+            let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Mno), Sort::new(columns::SortColumn::Abc)]).unwrap();
+            let sorter = parquetry_sort::ExternalSorter::new(sort_key).with_memory_budget(memory_budget as usize);
+
+            for value in &values {
+                sorter.insert(value).unwrap();
+            }
+
+            sorter.write_file(&test_file_path, WriterProperties::builder(), 1028 * 1028, |_| 1, false).unwrap();
+
+            let read_file = std::fs::File::open(test_file_path).unwrap();
+            let read_options = parquet::file::serialized_reader::ReadOptionsBuilder::new().build();
+            let read_values = Simple::read(read_file, read_options).collect::<Result<Vec<_>, _>>().unwrap();
+
+            let mut by_fields = values.clone();
+            by_fields.sort_by_key(|value| (value.mno, value.abc));
+
+            read_values == by_fields
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn read_in_memory_and_mapped(values: Vec<Simple>) -> bool {
+            let test_dir = tempfile::Builder::new().prefix("Simple-in-memory-data").tempdir().unwrap();
+            let test_file_path = test_dir.path().join("in-memory-data.parquet");
+
+            let mut buffer = vec![];
+            Simple::write_row_groups(&mut buffer, WriterProperties::default(), vec![values.clone()]).unwrap();
+            std::fs::write(&test_file_path, &buffer).unwrap();
+
+            let from_slice = Simple::read_slice(&buffer, ReadOptionsBuilder::new().build())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let from_bytes = Simple::read_bytes(buffer, ReadOptionsBuilder::new().build())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let from_path = Simple::read_path(&test_file_path, ReadOptionsBuilder::new().build())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            // Safety: the file is private to this test and is not modified while it is mapped.
+            let from_mmap = unsafe { Simple::read_mmap(&test_file_path, ReadOptionsBuilder::new().build()) }
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            from_slice == values && from_bytes == values && from_path == values && from_mmap == values
+        }
+    }
+
+    #[test]
+    fn read_path_missing_file() {
+        let result = Simple::read_path(
+            "missing-file.parquet",
+            ReadOptionsBuilder::new().build(),
+        )
+        .collect::<Result<Vec<_>, _>>();
+
+        assert!(matches!(result, Err(parquetry::error::Error::Io(_))));
+    }
+
+    #[test]
+    fn sort_key_empty() {
+        assert!(matches!(
+            Simple::sort_key(&[]),
+            Err(parquetry::error::SortKeyError::EmptySortKey)
+        ));
+    }
+
     #[test]
     fn sort_db_colliding_key_stability() -> Result<(), Box<dyn std::error::Error>> {
         let test_db_dir = tempfile::Builder::new()
@@ -185,6 +629,585 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn external_sort_stable() -> Result<(), Box<dyn std::error::Error>> {
+        let test_parquet_dir = tempfile::Builder::new()
+            .prefix("Simple-external-stable-sort-data")
+            .tempdir()?;
+        let test_file_path = test_parquet_dir
+            .path()
+            .join("external-stable-sort-data.parquet");
+
+        let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Abc)])?;
+
+        let now = Utc::now();
+
+        let values = (0..10000)
+            .map(|index| simple_instance(index % 100, &index.to_string(), now))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut expected = values.clone();
+        expected.sort_by_key(|simple| simple.abc);
+
+        let sorter = parquetry_sort::ExternalSorter::<Simple>::new(sort_key)
+            .with_memory_budget(4096)
+            .with_temp_dir(test_parquet_dir.path());
+
+        for value in &values {
+            sorter.insert(value)?;
+        }
+
+        assert!(sorter.run_count() > 1);
+
+        sorter.write_file(
+            &test_file_path,
+            WriterProperties::builder(),
+            1028 * 1028,
+            |_| 1,
+            false,
+        )?;
+
+        let read_file = std::fs::File::open(test_file_path)?;
+        let read_options = parquet::file::serialized_reader::ReadOptionsBuilder::new().build();
+        let read_values = Simple::read(read_file, read_options).collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(read_values, expected);
+
+        Ok(())
+    }
+
+    quickcheck::quickcheck! {
+        fn read_matching_bloom_filter(groups: Vec<Vec<Simple>>, index: usize) -> bool {
+            let test_dir = tempfile::Builder::new().prefix("Simple-bloom-data").tempdir().unwrap();
+            let test_file_path = test_dir.path().join("bloom-data.parquet");
+            let test_file = std::fs::File::create(&test_file_path).unwrap();
+
+            let properties = WriterProperties::builder()
+                .set_column_bloom_filter_enabled(columns::ABC.path(), true)
+                .build();
+
+            Simple::write_row_groups(test_file, properties, groups.clone()).unwrap();
+
+            let values = groups.into_iter().flatten().collect::<Vec<_>>();
+            let target = values.get(index % values.len().max(1)).map_or(0, |value| value.abc);
+
+            let read_file = std::fs::File::open(test_file_path).unwrap();
+            let read_values = Simple::read_matching(read_file, ReadOptionsBuilder::new(), &columns::ABC, &target)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            let expected = values.into_iter().filter(|value| value.abc == target).collect::<Vec<_>>();
+
+            read_values == expected
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn read_matching_repeated_column(groups: Vec<Vec<Simple>>, target: bool) -> bool {
+            let mut buffer = vec![];
+            Simple::write_row_groups(&mut buffer, WriterProperties::default(), groups.clone()).unwrap();
+
+            let read_values = Simple::read_matching(bytes::Bytes::from(buffer), ReadOptionsBuilder::new(), &columns::GHI, &target)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            let expected = groups
+                .into_iter()
+                .flatten()
+                .filter(|value| value.ghi.contains(&Some(target)))
+                .collect::<Vec<_>>();
+
+            read_values == expected
+        }
+    }
+
+    #[test]
+    fn read_matching_skips_row_groups() -> Result<(), Box<dyn std::error::Error>> {
+        let now = Utc::now();
+        let groups = (0..4)
+            .map(|group| {
+                (0..16)
+                    .map(|index| simple_instance(group * 100 + index % 8, "foo", now))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let properties = WriterProperties::builder()
+            .set_column_bloom_filter_enabled(columns::ABC.path(), true)
+            .build();
+
+        let mut buffer = vec![];
+        Simple::write_row_groups(&mut buffer, properties, groups.clone())?;
+        let buffer = bytes::Bytes::from(buffer);
+
+        let file_reader = parquet::file::serialized_reader::SerializedFileReader::new_with_options(
+            buffer.clone(),
+            ReadOptionsBuilder::new()
+                .with_reader_properties(
+                    parquet::file::properties::ReaderProperties::builder()
+                        .set_read_bloom_filter(true)
+                        .build(),
+                )
+                .build(),
+        )?;
+
+        assert_eq!(
+            parquetry::bloom::matching_row_groups(&file_reader, &columns::ABC, &203_u64)?,
+            vec![2]
+        );
+
+        let read_values = Simple::read_matching(
+            buffer.clone(),
+            ReadOptionsBuilder::new(),
+            &columns::ABC,
+            &203_u64,
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(
+            read_values,
+            vec![groups[2][3].clone(), groups[2][11].clone()]
+        );
+
+        let read_values =
+            Simple::read_matching(buffer, ReadOptionsBuilder::new(), &columns::ABC, &1_000_u64)
+                .collect::<Result<Vec<_>, _>>()?;
+
+        assert!(read_values.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn bloom_filter_small_ints() -> Result<(), Box<dyn std::error::Error>> {
+        use parquet::{
+            data_type::Int32Type,
+            file::{reader::FileReader, writer::SerializedFileWriter},
+        };
+
+        const SMALL: parquetry::ColumnInfo = parquetry::ColumnInfo {
+            index: 0,
+            path: &["small"],
+        };
+
+        let schema = std::sync::Arc::new(parquet::schema::parser::parse_message_type(
+            "message small_ints { required int32 small (INTEGER(8, true)); }",
+        )?);
+        let properties = WriterProperties::builder()
+            .set_column_bloom_filter_enabled(SMALL.path(), true)
+            .build();
+
+        let mut buffer = vec![];
+        let mut file_writer =
+            SerializedFileWriter::new(&mut buffer, schema, std::sync::Arc::new(properties))?;
+
+        for values in [[1, 2, 3], [-5, 4, -5]] {
+            let mut row_group_writer = file_writer.next_row_group()?;
+            let mut column_writer = row_group_writer
+                .next_column()?
+                .ok_or("missing column writer")?;
+            column_writer
+                .typed::<Int32Type>()
+                .write_batch(&values, None, None)?;
+            column_writer.close()?;
+            row_group_writer.close()?;
+        }
+
+        file_writer.close()?;
+
+        let file_reader = parquet::file::serialized_reader::SerializedFileReader::new_with_options(
+            bytes::Bytes::from(buffer),
+            ReadOptionsBuilder::new()
+                .with_reader_properties(
+                    parquet::file::properties::ReaderProperties::builder()
+                        .set_read_bloom_filter(true)
+                        .build(),
+                )
+                .build(),
+        )?;
+
+        assert_eq!(file_reader.num_row_groups(), 2);
+        assert_eq!(
+            parquetry::bloom::matching_row_groups(&file_reader, &SMALL, &-5_i8)?,
+            vec![1]
+        );
+        assert_eq!(
+            parquetry::bloom::matching_row_groups(&file_reader, &SMALL, &2_u8)?,
+            vec![0]
+        );
+        assert_eq!(
+            parquetry::bloom::matching_rows(&file_reader, &SMALL, &-5_i16)?,
+            vec![false, false, false, true, false, true]
+        );
+        assert_eq!(
+            parquetry::bloom::matching_rows(&file_reader, &SMALL, &4_u16)?,
+            vec![false, false, false, false, true, false]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_matching_bloom_filter_invalid_value() {
+        let test_dir = tempfile::Builder::new()
+            .prefix("Simple-bloom-invalid-data")
+            .tempdir()
+            .unwrap();
+        let test_file_path = test_dir.path().join("bloom-invalid-data.parquet");
+        let test_file = std::fs::File::create(&test_file_path).unwrap();
+
+        Simple::write_row_groups(test_file, WriterProperties::default(), vec![vec![]]).unwrap();
+
+        let read_file = std::fs::File::open(test_file_path).unwrap();
+        let result = Simple::read_matching(
+            read_file,
+            ReadOptionsBuilder::new(),
+            &columns::ABC,
+            "not a u64",
+        )
+        .collect::<Result<Vec<_>, _>>();
+
+        assert!(matches!(
+            result,
+            Err(parquetry::error::Error::InvalidBloomFilterValue(_))
+        ));
+    }
+
+    #[test]
+    fn footer_metadata_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let test_dir = tempfile::Builder::new()
+            .prefix("Simple-metadata-data")
+            .tempdir()?;
+        let test_file_path = test_dir.path().join("metadata-data.parquet");
+
+        let options = MetadataOptions::new()
+            .with_source()
+            .with_hash()
+            .with_version("1.2.3")
+            .with_key_value("origin", "test");
+
+        let mut writer = Simple::writer_with_metadata(
+            std::fs::File::create(&test_file_path)?,
+            WriterProperties::builder(),
+            &options,
+        )?;
+        writer.write_item(&simple_instance(1, "foo", Utc::now())?)?;
+        writer.finish_row_group()?;
+        writer.finish()?;
+
+        let metadata = Simple::read_metadata(std::fs::File::open(&test_file_path)?)?;
+
+        assert_eq!(metadata.source.as_deref(), Some(Simple::source()));
+        assert_eq!(
+            metadata.hash,
+            Some(parquetry::metadata::source_hash(Simple::source()))
+        );
+        assert_eq!(metadata.version.as_deref(), Some("1.2.3"));
+        assert_eq!(
+            metadata.key_value.get("origin"),
+            Some(&Some("test".to_string()))
+        );
+
+        let read_values = Simple::read_checked(
+            std::fs::File::open(&test_file_path)?,
+            ReadOptionsBuilder::new().build(),
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(read_values.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_checked_missing_metadata() -> Result<(), Box<dyn std::error::Error>> {
+        let test_dir = tempfile::Builder::new()
+            .prefix("Simple-missing-metadata-data")
+            .tempdir()?;
+        let test_file_path = test_dir.path().join("missing-metadata-data.parquet");
+
+        Simple::write_row_groups(
+            std::fs::File::create(&test_file_path)?,
+            WriterProperties::default(),
+            vec![vec![simple_instance(1, "foo", Utc::now())?]],
+        )?;
+
+        let result = Simple::read_checked(
+            std::fs::File::open(&test_file_path)?,
+            ReadOptionsBuilder::new().build(),
+        )
+        .collect::<Result<Vec<_>, _>>();
+
+        assert!(matches!(
+            result,
+            Err(parquetry::error::Error::SchemaMismatch { found: None, .. })
+        ));
+
+        Ok(())
+    }
+
+    fn encryption_key(parts: (u64, u64)) -> Vec<u8> {
+        parts
+            .0
+            .to_le_bytes()
+            .into_iter()
+            .chain(parts.1.to_le_bytes())
+            .collect()
+    }
+
+    quickcheck::quickcheck! {
+        fn round_trip_encrypted(values: Vec<Simple>, footer_key: (u64, u64), column_key: (u64, u64)) -> bool {
+            let test_dir = tempfile::Builder::new().prefix("Simple-encrypted-data").tempdir().unwrap();
+            let test_file_path = test_dir.path().join("encrypted-data.parquet");
+            let test_file = std::fs::File::create(&test_file_path).unwrap();
+
+            let keys = EncryptionKeys::new(encryption_key(footer_key))
+                .with_column_key(&columns::DEF, encryption_key(column_key))
+                .with_column_key(&columns::REQ_DEF, encryption_key(column_key));
+
+            let mut writer = Simple::encrypted_writer(test_file, WriterProperties::builder(), &keys).unwrap();
+            writer.write_row_group::<parquetry::error::Error, _>(&mut values.iter().map(Ok)).unwrap();
+            writer.finish().unwrap();
+
+            let read_file = std::fs::File::open(test_file_path).unwrap();
+            let read_values = Simple::read_encrypted(read_file, ReadOptionsBuilder::new(), &keys)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            read_values == values
+        }
+    }
+
+    fn round_trip_encrypted_values<
+        T: Schema + TryFrom<parquet::record::Row, Error = parquetry::error::Error> + PartialEq,
+    >(
+        values: &[T],
+        keys: &EncryptionKeys,
+    ) -> bool {
+        let mut buffer = vec![];
+
+        let mut writer =
+            T::encrypted_writer(&mut buffer, WriterProperties::builder(), keys).unwrap();
+        writer
+            .write_row_group::<parquetry::error::Error, _>(&mut values.iter().map(Ok))
+            .unwrap();
+        writer.finish().unwrap();
+
+        let read_values =
+            T::read_encrypted(bytes::Bytes::from(buffer), ReadOptionsBuilder::new(), keys)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+        read_values == values
+    }
+
+    quickcheck::quickcheck! {
+        fn round_trip_encrypted_nested(values: Vec<Nested>, footer_key: (u64, u64), column_key: (u64, u64)) -> bool {
+            let keys = EncryptionKeys::new(encryption_key(footer_key))
+                .with_column_key(&nested::columns::foox::bar::quxes::XUQES, encryption_key(column_key))
+                .with_column_key(&nested::columns::foox::bar::quxes::OOF, encryption_key(column_key));
+
+            round_trip_encrypted_values(&values, &keys)
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn round_trip_encrypted_two_list_levels(values: Vec<TwoListLevels>, footer_key: (u64, u64)) -> bool {
+            let keys = EncryptionKeys::new(encryption_key(footer_key));
+
+            round_trip_encrypted_values(&values, &keys)
+        }
+    }
+
+    #[test]
+    fn read_encrypted_row_groups() -> Result<(), Box<dyn std::error::Error>> {
+        let keys = EncryptionKeys::new(encryption_key((1, 2)));
+        let now = Utc::now();
+        let groups = vec![
+            vec![
+                simple_instance(1, "foo", now)?,
+                simple_instance(2, "bar", now)?,
+            ],
+            vec![simple_instance(3, "baz", now)?],
+            vec![],
+            vec![
+                simple_instance(4, "qux", now)?,
+                simple_instance(5, "quux", now)?,
+            ],
+        ];
+
+        let mut buffer = vec![];
+        let mut writer = Simple::encrypted_writer(&mut buffer, WriterProperties::builder(), &keys)?;
+
+        for group in &groups {
+            writer.write_row_group::<parquetry::error::Error, _>(&mut group.iter().map(Ok))?;
+        }
+
+        writer.finish()?;
+
+        let buffer = bytes::Bytes::from(buffer);
+
+        // The empty row group is dropped, so the last row group has index 2 in the decrypted copy.
+        for (index, expected) in [(0, &groups[0]), (1, &groups[1]), (2, &groups[3])] {
+            let read_values = Simple::read_encrypted(
+                buffer.clone(),
+                ReadOptionsBuilder::new()
+                    .with_predicate(Box::new(move |_, row_group_index| row_group_index == index)),
+                &keys,
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(&read_values, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_encrypted_wrong_key() -> Result<(), Box<dyn std::error::Error>> {
+        let test_dir = tempfile::Builder::new()
+            .prefix("Simple-wrong-key-data")
+            .tempdir()?;
+        let test_file_path = test_dir.path().join("wrong-key-data.parquet");
+
+        let keys = EncryptionKeys::new(encryption_key((1, 2)))
+            .with_column_key(&columns::DEF, encryption_key((3, 4)));
+
+        let mut writer = Simple::encrypted_writer(
+            std::fs::File::create(&test_file_path)?,
+            WriterProperties::builder(),
+            &keys,
+        )?;
+        writer.write_item(&simple_instance(1, "foo", Utc::now())?)?;
+        writer.finish_row_group()?;
+        writer.finish()?;
+
+        let wrong_keys = EncryptionKeys::new(encryption_key((5, 6)))
+            .with_column_key(&columns::DEF, encryption_key((3, 4)));
+
+        let result = Simple::read_encrypted(
+            std::fs::File::open(&test_file_path)?,
+            ReadOptionsBuilder::new(),
+            &wrong_keys,
+        )
+        .collect::<Result<Vec<_>, _>>();
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn encryption_keys_debug_redacted() {
+        let keys = EncryptionKeys::new(b"0123456789abcdef".to_vec())
+            .with_column_key(&columns::DEF, b"fedcba9876543210".to_vec());
+
+        let debug = format!("{keys:?}");
+
+        assert!(debug.contains("def"));
+        assert!(!debug.contains("0123456789abcdef"));
+        assert!(!debug.contains("fedcba9876543210"));
+        assert!(!debug.contains("48, 49, 50"));
+    }
+
+    #[test]
+    fn merge_sorted_missing_sorting_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Abc)])?;
+        let now = Utc::now();
+
+        let mut input = vec![];
+        Simple::write_row_groups(
+            &mut input,
+            WriterProperties::default(),
+            vec![vec![simple_instance(1, "foo", now)?]],
+        )?;
+
+        let result = parquetry_sort::merge_sorted(
+            vec![bytes::Bytes::from(input)],
+            &sort_key,
+            Vec::<u8>::new(),
+            WriterProperties::builder(),
+            100,
+            |_: &Simple| 1,
+            false,
+        );
+
+        assert!(matches!(
+            result,
+            Err(parquetry_sort::Error::SortingColumnsMismatch {
+                input_index: 0,
+                row_group_index: 0
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_sorted_negative_values() -> Result<(), Box<dyn std::error::Error>> {
+        let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Stu)])?;
+        let values = [-86_400_000_000, -1, 0, 1, 86_400_000_000]
+            .into_iter()
+            .map(|micros| {
+                let stu = DateTime::from_timestamp_micros(micros).ok_or("invalid timestamp")?;
+                Ok(simple_instance(1, "foo", stu)?)
+            })
+            .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+        let metadata = Simple::write_row_groups_sorted(
+            Vec::<u8>::new(),
+            WriterProperties::builder(),
+            &sort_key,
+            vec![values.clone()],
+        )?;
+
+        let expected_sorting_columns: Vec<_> = (&sort_key).into();
+        assert_eq!(
+            metadata.row_groups()[0].sorting_columns(),
+            Some(&expected_sorting_columns)
+        );
+
+        let mut reversed = values;
+        reversed.reverse();
+
+        let result = Simple::write_row_groups_sorted(
+            Vec::<u8>::new(),
+            WriterProperties::builder(),
+            &sort_key,
+            vec![reversed],
+        );
+
+        assert!(matches!(
+            result,
+            Err(parquetry::error::Error::UnsortedValue { row_index: 1 })
+        ));
+
+        Ok(())
+    }
+
+    fn sort_db_with_policy(
+        values: &[Simple],
+        policy: parquetry_sort::MergePolicy<Simple>,
+    ) -> Result<Vec<Simple>, Box<dyn std::error::Error>> {
+        let test_db_dir = tempfile::Builder::new()
+            .prefix("Simple-policy-sort-db")
+            .tempdir()?;
+        let mut buffer = vec![];
+
+        let sort_key = Simple::sort_key(&[Sort::new(columns::SortColumn::Abc)])?;
+        let sort_db =
+            parquetry_sort::SortDb::open_with_policy(test_db_dir.path(), sort_key, policy)?;
+
+        for value in values {
+            sort_db.insert(value)?;
+        }
+
+        sort_db.write(&mut buffer, WriterProperties::builder(), 1028 * 1028, |_| 1, false)?;
+
+        Ok(Simple::read_bytes(buffer, ReadOptionsBuilder::new().build())
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
     fn simple_instance(
         abc: u64,
         req_def: &str,